@@ -0,0 +1,87 @@
+//! Derive macro for grid tiles.
+//!
+//! ```ignore
+//! #[derive(GridTile)]
+//! enum Tile {
+//!     #[tile('.')]
+//!     GardenPlot,
+//!     #[tile('#')]
+//!     Rock,
+//! }
+//! ```
+//!
+//! Implements `crate::lib::grid::GridTile`, mapping each variant to and from the character in its `#[tile]` attribute,
+//! and `Display`, rendering each variant as that character.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::LitChar;
+
+#[proc_macro_derive(GridTile, attributes(tile))]
+pub fn derive_grid_tile(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match grid_tile(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn grid_tile(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(input.span(), "GridTile can only be derived for enums"));
+    };
+
+    let mut variants: Vec<(&syn::Ident, LitChar)> = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(variant.span(), "GridTile variants can not have fields"));
+        }
+
+        let mut tile_attrs = variant.attrs.iter().filter(|attr| attr.path().is_ident("tile"));
+        let attr = tile_attrs
+            .next()
+            .ok_or_else(|| syn::Error::new(variant.span(), "Missing #[tile('c')] attribute"))?;
+        if let Some(attr) = tile_attrs.next() {
+            return Err(syn::Error::new(attr.span(), "Duplicate #[tile] attribute"));
+        }
+        let c: LitChar = attr.parse_args()?;
+
+        if let Some((other, _)) = variants.iter().find(|(_, other)| other.value() == c.value()) {
+            return Err(syn::Error::new(c.span(), format!("{:?} is already used by {}", c.value(), other)));
+        }
+        variants.push((&variant.ident, c));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let from_char = variants.iter().map(|(ident, c)| quote! { #c => Some(Self::#ident), });
+    let to_char = variants.iter().map(|(ident, c)| quote! { Self::#ident => #c, });
+
+    Ok(quote! {
+        impl #impl_generics crate::lib::grid::GridTile for #name #ty_generics #where_clause {
+            fn from_char(c: char) -> Option<Self> {
+                match c {
+                    #(#from_char)*
+                    _ => None,
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    #(#to_char)*
+                }
+            }
+        }
+
+        impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                use std::fmt::Write as _;
+                f.write_char(crate::lib::grid::GridTile::to_char(self))
+            }
+        }
+    })
+}
@@ -0,0 +1,148 @@
+//! Known answers, to check solutions against.
+//!
+//! Answers are kept in `answers.toml`, with a table per day and a key per part:
+//!
+//! ```toml
+//! [day_01]
+//! part_1 = 53194
+//! part_2 = "54249"
+//! ```
+//!
+//! Answers may be written as integers or strings, they are compared with the rendered answer.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+#[derive(Debug, derive_more::Display)]
+pub enum Error {
+    #[display(fmt = "Failed to read {}: {}", "path.display()", source)]
+    Read { path: PathBuf, source: std::io::Error },
+
+    #[display(fmt = "Failed to parse answers: {}", _0)]
+    Toml(toml::de::Error),
+
+    #[display(fmt = "Invalid answer `{}`: {}", key, reason)]
+    Invalid { key: String, reason: &'static str },
+}
+impl std::error::Error for Error {}
+
+/// Outcome of checking an answer against the known answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+    },
+
+    /// There is no known answer.
+    Unknown,
+}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(usize, usize), String>,
+}
+impl Answers {
+    /// Loads the answers at the given path.
+    ///
+    /// A missing file is the same as an empty file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(Error::Read {
+                path: path.to_owned(),
+                source,
+            }),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let table: toml::Table = s.parse().map_err(Error::Toml)?;
+
+        let mut answers = HashMap::new();
+        for (day_key, parts) in table {
+            let day = number(&day_key, "day_").ok_or(Error::Invalid {
+                key: day_key.clone(),
+                reason: "expected a table named like `day_01`",
+            })?;
+            let toml::Value::Table(parts) = parts else {
+                return Err(Error::Invalid {
+                    key: day_key,
+                    reason: "expected a table of parts",
+                });
+            };
+            for (part_key, answer) in parts {
+                let key = format!("{day_key}.{part_key}");
+                let part = number(&part_key, "part_").ok_or(Error::Invalid {
+                    key: key.clone(),
+                    reason: "expected a key named like `part_1`",
+                })?;
+                let answer = match answer {
+                    toml::Value::Integer(answer) => answer.to_string(),
+                    toml::Value::String(answer) => answer,
+                    _ => {
+                        return Err(Error::Invalid {
+                            key,
+                            reason: "expected an integer or a string",
+                        });
+                    }
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: usize, part: usize, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Unknown,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+}
+
+/// Parses the number in keys like `day_01`.
+fn number(key: &str, prefix: &str) -> Option<usize> {
+    key.strip_prefix(prefix)?.parse().ok()
+}
+
+#[test]
+fn check_answers() {
+    let answers = Answers::parse(
+        r#"
+[day_01]
+part_1 = 142
+part_2 = "281"
+
+[day_25]
+part_1 = 54
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(answers.check(1, 1, "142"), Verdict::Pass);
+    assert_eq!(answers.check(1, 2, "281"), Verdict::Pass);
+    assert_eq!(answers.check(25, 1, "55"), Verdict::Fail { expected: "54".to_owned() });
+    assert_eq!(answers.check(25, 2, "54"), Verdict::Unknown);
+}
+
+#[test]
+fn invalid_answers() {
+    assert!(matches!(Answers::parse("[day_x]\npart_1 = 1\n"), Err(Error::Invalid { .. })));
+    assert!(matches!(Answers::parse("[day_01]\nfirst = 1\n"), Err(Error::Invalid { .. })));
+    assert!(matches!(Answers::parse("[day_01]\npart_1 = 1.5\n"), Err(Error::Invalid { .. })));
+    assert!(matches!(Answers::parse("[day_01"), Err(Error::Toml(_))));
+}
@@ -0,0 +1,171 @@
+//! Benchmarking of solutions.
+//!
+//! Each part is run a number of times after a warmup, and the run times are summarized as min, median and 95th percentile.
+//! Time spent in the shared parsers (see [`crate::lib::parse_time`]) during a run is reported as parse time, and the rest of the run as solve time.
+
+use crate::inputs::Inputs;
+use crate::lib::parse_time;
+use crate::Error;
+use std::cell::Cell;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Number of timed runs, at least one.
+    pub iterations: usize,
+
+    /// Number of untimed runs before the timed runs.
+    pub warmup: usize,
+}
+
+/// Summary of run times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "nanos")]
+    pub min: Duration,
+
+    #[serde(rename = "median_ns", serialize_with = "nanos")]
+    pub median: Duration,
+
+    #[serde(rename = "p95_ns", serialize_with = "nanos")]
+    pub p95: Duration,
+}
+impl Stats {
+    /// Summarizes the given (non-empty) samples.
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "No samples");
+        samples.sort_unstable();
+
+        // Nearest rank
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+
+        Self {
+            min: samples[0],
+            median: percentile(50),
+            p95: percentile(95),
+        }
+    }
+}
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { min, median, p95 } = self;
+        write!(
+            f,
+            "{:>10} {:>10} {:>10}",
+            format!("{min:.1?}"),
+            format!("{median:.1?}"),
+            format!("{p95:.1?}")
+        )
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct Report {
+    pub day: usize,
+    pub part: usize,
+    pub iterations: usize,
+
+    /// Time spent parsing, if the part parses with the shared parsers.
+    pub parse: Option<Stats>,
+
+    /// Time spent outside of parsing, if the part parses with the shared parsers.
+    pub solve: Option<Stats>,
+
+    pub total: Stats,
+}
+
+pub fn bench(inputs: &Inputs, day: usize, part: usize, options: Options) -> Result<Report, Error> {
+    let part_fn = crate::find_part(day, part)?;
+    let input = inputs.get(day)?;
+    let iterations = options.iterations;
+    parse_time::set_hook(Some(add_parse_time));
+
+    for _ in 0..options.warmup {
+        part_fn(&input).map_err(|source| Error::Solve { day, part, source })?;
+    }
+
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut total_samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        PARSE_TIME.take();
+        let now = Instant::now();
+        part_fn(&input).map_err(|source| Error::Solve { day, part, source })?;
+        total_samples.push(now.elapsed());
+        parse_samples.extend(PARSE_TIME.take());
+    }
+
+    // Only split the run time if every run parsed
+    let (parse, solve) = if parse_samples.len() == total_samples.len() {
+        let solve_samples = total_samples
+            .iter()
+            .zip(&parse_samples)
+            .map(|(total, parse)| total.saturating_sub(*parse))
+            .collect();
+        (Some(Stats::new(parse_samples)), Some(Stats::new(solve_samples)))
+    } else {
+        (None, None)
+    };
+
+    Ok(Report {
+        day,
+        part,
+        iterations,
+        parse,
+        solve,
+        total: Stats::new(total_samples),
+    })
+}
+
+thread_local! {
+    /// Time spent parsing on this thread since it was last taken, if any.
+    static PARSE_TIME: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Parse time hook, adding up the time spent parsing.
+fn add_parse_time(elapsed: Duration) {
+    PARSE_TIME.set(Some(PARSE_TIME.get().unwrap_or_default() + elapsed));
+}
+
+/// Prints a header for the rows printed by [`print_row`].
+pub fn print_header() {
+    let columns = format!("{:>10} {:>10} {:>10}", "min", "median", "p95");
+    println!("{:16}{:<32}   {:<32}   total", "", "parse", "solve");
+    println!("{:16}{columns}   {columns}   {columns}", "");
+}
+
+pub fn print_row(report: &Report) {
+    let missing = format!("{:>10} {:>10} {:>10}", "n/a", "n/a", "n/a");
+    let parse = report.parse.map_or(missing.clone(), |stats| stats.to_string());
+    let solve = report.solve.map_or(missing, |stats| stats.to_string());
+    println!("Day {:>2} Part {}   {parse}   {solve}   {}", report.day, report.part, report.total);
+}
+
+fn nanos<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos().try_into().unwrap_or(u64::MAX))
+}
+
+#[test]
+fn parse_time() {
+    PARSE_TIME.take();
+    add_parse_time(Duration::from_millis(2));
+    add_parse_time(Duration::from_millis(3));
+    assert_eq!(PARSE_TIME.take(), Some(Duration::from_millis(5)));
+    assert_eq!(PARSE_TIME.take(), None);
+}
+
+#[test]
+fn stats() {
+    let samples = (1..=20).rev().map(Duration::from_millis).collect();
+    assert_eq!(
+        Stats::new(samples),
+        Stats {
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(10),
+            p95: Duration::from_millis(19),
+        }
+    );
+
+    let single = Stats::new(vec![Duration::from_micros(7)]);
+    assert_eq!(single.min, single.p95);
+}
@@ -1,149 +1,81 @@
 //! https://adventofcode.com/2023/day/1
 
-use type_toppings::ResultExt as _;
-
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    parser::parse_part_1(input)
-        .into_iter()
-        .map(|DigitPair { first_digit, last_digit }| format!("{}{}", first_digit, last_digit))
-        .map(|s| s.parse::<usize>().unwrap_or_report())
-        .sum::<usize>()
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse_part_1(input)?.iter().map(DigitPair::calibration_value).sum::<usize>())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    parser::parse_part_2(input)
-        .into_iter()
-        .map(|DigitPair { first_digit, last_digit }| format!("{}{}", first_digit, last_digit))
-        .map(|s| s.parse::<usize>().unwrap_or_report())
-        .sum::<usize>()
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse_part_2(input)?.iter().map(DigitPair::calibration_value).sum::<usize>())
 }
 
 struct DigitPair {
-    first_digit: char,
-    last_digit: char,
+    first_digit: usize,
+    last_digit: usize,
+}
+impl DigitPair {
+    fn calibration_value(&self) -> usize {
+        10 * self.first_digit + self.last_digit
+    }
 }
 
 mod parser {
     use super::*;
-
-    pub(super) fn parse_part_1(s: &str) -> Vec<DigitPair> {
-        s.lines().map(|line| -> DigitPair { parse_line_part_1(line) }).collect()
-    }
-
-    pub(super) fn parse_part_2(s: &str) -> Vec<DigitPair> {
-        s.lines().map(|line| -> DigitPair { parse_line_part_2(line) }).collect()
+    use crate::my_nom_prelude::*;
+
+    const DIGITS: [(&str, usize); 10] = [
+        ("0", 0),
+        ("1", 1),
+        ("2", 2),
+        ("3", 3),
+        ("4", 4),
+        ("5", 5),
+        ("6", 6),
+        ("7", 7),
+        ("8", 8),
+        ("9", 9),
+    ];
+
+    const WORDS: [(&str, usize); 10] = [
+        ("zero", 0),
+        ("one", 1),
+        ("two", 2),
+        ("three", 3),
+        ("four", 4),
+        ("five", 5),
+        ("six", 6),
+        ("seven", 7),
+        ("eight", 8),
+        ("nine", 9),
+    ];
+
+    pub(super) fn parse_part_1(s: &str) -> Result<Vec<DigitPair>, SolveError> {
+        parse_lines(s, |line| digit_pair(line, &DIGITS))
     }
 
-    fn parse_line_part_1(s: &str) -> DigitPair {
-        let mut i = 0;
-        let first_digit = loop {
-            assert!(s.len() >= i, "first digit search out of bounds");
-            let mid = i;
-            let (_, s) = s.split_at(mid);
-            match s {
-                _ if s.starts_with('0') => break '0',
-                _ if s.starts_with('1') => break '1',
-                _ if s.starts_with('2') => break '2',
-                _ if s.starts_with('3') => break '3',
-                _ if s.starts_with('4') => break '4',
-                _ if s.starts_with('5') => break '5',
-                _ if s.starts_with('6') => break '6',
-                _ if s.starts_with('7') => break '7',
-                _ if s.starts_with('8') => break '8',
-                _ if s.starts_with('9') => break '9',
-                _ => {}
-            }
-            i += 1;
-        };
-
-        let mut i = 0;
-        let last_digit = loop {
-            assert!(s.len() >= i, "last digit search out of bounds");
-            let mid = s.len() - i;
-            let (s, _) = s.split_at(mid);
-            match s {
-                _ if s.ends_with('0') => break '0',
-                _ if s.ends_with('1') => break '1',
-                _ if s.ends_with('2') => break '2',
-                _ if s.ends_with('3') => break '3',
-                _ if s.ends_with('4') => break '4',
-                _ if s.ends_with('5') => break '5',
-                _ if s.ends_with('6') => break '6',
-                _ if s.ends_with('7') => break '7',
-                _ if s.ends_with('8') => break '8',
-                _ if s.ends_with('9') => break '9',
-                _ => {}
-            }
-            i += 1;
-        };
-
-        DigitPair { first_digit, last_digit }
+    pub(super) fn parse_part_2(s: &str) -> Result<Vec<DigitPair>, SolveError> {
+        let spellings = [DIGITS, WORDS].concat();
+        parse_lines(s, |line| digit_pair(line, &spellings))
     }
 
-    fn parse_line_part_2(s: &str) -> DigitPair {
-        let mut i = 0;
-        let first_digit = loop {
-            assert!(s.len() >= i, "first digit search out of bounds");
-            let mid = i;
-            let (_, s) = s.split_at(mid);
-            match s {
-                _ if s.starts_with('0') => break '0',
-                _ if s.starts_with('1') => break '1',
-                _ if s.starts_with('2') => break '2',
-                _ if s.starts_with('3') => break '3',
-                _ if s.starts_with('4') => break '4',
-                _ if s.starts_with('5') => break '5',
-                _ if s.starts_with('6') => break '6',
-                _ if s.starts_with('7') => break '7',
-                _ if s.starts_with('8') => break '8',
-                _ if s.starts_with('9') => break '9',
-                _ if s.starts_with("zero") => break '0',
-                _ if s.starts_with("one") => break '1',
-                _ if s.starts_with("two") => break '2',
-                _ if s.starts_with("three") => break '3',
-                _ if s.starts_with("four") => break '4',
-                _ if s.starts_with("five") => break '5',
-                _ if s.starts_with("six") => break '6',
-                _ if s.starts_with("seven") => break '7',
-                _ if s.starts_with("eight") => break '8',
-                _ if s.starts_with("nine") => break '9',
-                _ => {}
-            }
-            i += 1;
+    /// Finds the first and last digit in `line`, which may be spelled as any of `spellings`.
+    ///
+    /// Spellings may overlap, so `oneight` has the digits 1 and 8.
+    fn digit_pair<'a>(line: &'a str, spellings: &[(&str, usize)]) -> IResult<&'a str, DigitPair> {
+        let digit_at = |(i, _)| {
+            spellings
+                .iter()
+                .find(|(spelling, _)| line[i..].starts_with(spelling))
+                .map(|&(_, digit)| digit)
         };
-
-        let mut i = 0;
-        let last_digit = loop {
-            assert!(s.len() >= i, "last digit search out of bounds");
-            let mid = s.len() - i;
-            let (s, _) = s.split_at(mid);
-            match s {
-                _ if s.ends_with('0') => break '0',
-                _ if s.ends_with('1') => break '1',
-                _ if s.ends_with('2') => break '2',
-                _ if s.ends_with('3') => break '3',
-                _ if s.ends_with('4') => break '4',
-                _ if s.ends_with('5') => break '5',
-                _ if s.ends_with('6') => break '6',
-                _ if s.ends_with('7') => break '7',
-                _ if s.ends_with('8') => break '8',
-                _ if s.ends_with('9') => break '9',
-                _ if s.ends_with("zero") => break '0',
-                _ if s.ends_with("one") => break '1',
-                _ if s.ends_with("two") => break '2',
-                _ if s.ends_with("three") => break '3',
-                _ if s.ends_with("four") => break '4',
-                _ if s.ends_with("five") => break '5',
-                _ if s.ends_with("six") => break '6',
-                _ if s.ends_with("seven") => break '7',
-                _ if s.ends_with("eight") => break '8',
-                _ if s.ends_with("nine") => break '9',
-                _ => {}
-            }
-            i += 1;
+        let Some(first_digit) = line.char_indices().find_map(digit_at) else {
+            return Err(nom::Err::Error(nom::error::Error::new(line, nom::error::ErrorKind::Digit)));
         };
-
-        DigitPair { first_digit, last_digit }
+        let last_digit = line.char_indices().rev().find_map(digit_at).unwrap_or(first_digit);
+        Ok((&line[line.len()..], DigitPair { first_digit, last_digit }))
     }
 }
 
@@ -168,10 +100,22 @@ zoneight234
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE_PART_1).to_string(), "142");
+    assert_eq!(part_1(EXAMPLE_PART_1).unwrap().to_string(), "142");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE_PART_2).to_string(), "281");
+    assert_eq!(part_2(EXAMPLE_PART_2).unwrap().to_string(), "281");
+}
+
+#[test]
+fn line_without_digits() {
+    assert_eq!(
+        part_1("1abc2\npqrstu\n").err(),
+        Some(SolveError::Parse {
+            line: 2,
+            column: 1,
+            kind: nom::error::ErrorKind::Digit,
+        })
+    );
 }
@@ -1,18 +1,21 @@
 //! https://adventofcode.com/2023/day/2
 
+use crate::SolveError;
 use std::cmp::max;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    parser::parse(input)
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse(input)?
         .into_iter()
         .filter(|game| !game.reveals.iter().any(|reveal| reveal.red > 12 || reveal.green > 13 || reveal.blue > 14))
         .map(|game| game.nr)
-        .sum::<u64>()
+        .sum::<u64>())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
     let mut sum = 0;
-    for game in parser::parse(input) {
+    for game in parser::parse(input)? {
         let mut red = 0;
         let mut green = 0;
         let mut blue = 0;
@@ -24,7 +27,7 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
         let power = red * green * blue;
         sum += power;
     }
-    sum
+    Ok(sum)
 }
 
 struct Game {
@@ -42,10 +45,9 @@ struct Reveal {
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse(s: &str) -> Vec<Game> {
-        s.lines().map(|line| all_consuming(parse_line)(line).expect_or_report(line).1).collect()
+    pub(super) fn parse(s: &str) -> Result<Vec<Game>, SolveError> {
+        parse_lines(s, parse_line)
     }
 
     fn parse_line(s: &str) -> IResult<&str, Game> {
@@ -82,10 +84,25 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE).to_string(), "8");
+    assert_eq!(part_1(EXAMPLE).unwrap().to_string(), "8");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE).to_string(), "2286");
+    assert_eq!(part_2(EXAMPLE).unwrap().to_string(), "2286");
+}
+
+#[test]
+fn part_1_malformed_input() {
+    let Err(err) = part_1("Game 1: 3 blue\nGame x: 1 red\n") else {
+        panic!("Malformed input should not parse");
+    };
+    assert_eq!(
+        err,
+        SolveError::Parse {
+            line: 2,
+            column: 6,
+            kind: nom::error::ErrorKind::Digit
+        }
+    );
 }
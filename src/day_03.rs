@@ -1,14 +1,18 @@
 //! https://adventofcode.com/2023/day/3
 
-use std::cmp::max;
-use std::cmp::min;
+use crate::lib::grid;
+use crate::lib::grid::Grid as _;
+use crate::lib::grid::Pos;
+use crate::SolveError;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let grid = grid::vec_of_vecs::Grid::try_parse_char_grid(input, parse_tile)?;
 
     let mut sum = 0;
 
-    for (row, line) in lines.iter().enumerate() {
+    for (row, line) in grid.rows.iter().enumerate() {
         let mut n = 0;
         let mut is_part_number = false;
 
@@ -18,17 +22,9 @@ pub fn part_1(input: &str) -> impl std::fmt::Display {
                 n += d;
 
                 // Check for adjacent symbols
-                let row_lower = max(1, row) - 1;
-                let row_upper = min(lines.len() - 1, row + 1);
-                let col_lower = max(1, col) - 1;
-                let col_upper = min(line.len() - 1, col + 1);
-                for r in row_lower..=row_upper {
-                    for c in col_lower..=col_upper {
-                        let x = lines[r][c];
-                        if x != '.' && !x.is_ascii_digit() {
-                            is_part_number = true;
-                        }
-                    }
+                let pos = Pos(row as isize, col as isize);
+                if grid.neighbours8(pos).any(|(_pos, &x)| x != '.' && !x.is_ascii_digit()) {
+                    is_part_number = true;
                 }
             } else {
                 if is_part_number {
@@ -45,64 +41,69 @@ pub fn part_1(input: &str) -> impl std::fmt::Display {
         }
     }
 
-    sum
+    Ok(sum)
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+#[allow(clippy::needless_range_loop)]
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let grid = grid::vec_of_vecs::Grid::try_parse_char_grid(input, parse_tile)?;
+    let lines = &grid.rows;
     let mut sum = 0;
-    for (row, line) in lines.iter().enumerate() {
-        for (col, char) in line.iter().enumerate() {
-            if *char == '*' {
-                let row_lower = max(1, row) - 1;
-                let row_upper = min(lines.len() - 1, row + 1);
-                let col_lower = max(1, col) - 1;
-                let col_upper = min(line.len() - 1, col + 1);
-                let mut part_numbers: Vec<PartNumber> = vec![];
-                for r in row_lower..=row_upper {
-                    for c in col_lower..=col_upper {
-                        if !lines[r][c].is_ascii_digit() {
-                            continue;
-                        }
-                        // Expand left
-                        let mut left = c;
-                        while left > 0 && lines[r][left - 1].is_ascii_digit() {
-                            left -= 1;
-                        }
-                        // Expand right
-                        let mut right = c;
-                        while right + 1 < lines[r].len() && lines[r][right + 1].is_ascii_digit() {
-                            right += 1;
-                        }
-                        let mut part_number = 0;
-                        for i in left..=right {
-                            part_number *= 10;
-                            part_number += lines[r][i].to_digit(10).unwrap();
-                        }
-                        let part_number = PartNumber {
-                            row: r,
-                            first_digit_col: left,
-                            value: part_number,
-                        };
-                        if !part_numbers
-                            .iter().any(|p| p.row == part_number.row && p.first_digit_col == part_number.first_digit_col)
-                        {
-                            part_numbers.push(part_number);
-                        }
-                    }
+    for (pos, char) in grid.iter() {
+        if *char == '*' {
+            let mut part_numbers: Vec<PartNumber> = vec![];
+            for (Pos(r, c), tile) in grid.neighbours8(pos) {
+                if !tile.is_ascii_digit() {
+                    continue;
+                }
+                let (r, c) = (r as usize, c as usize);
+                // Expand left
+                let mut left = c;
+                while left > 0 && lines[r][left - 1].is_ascii_digit() {
+                    left -= 1;
+                }
+                // Expand right
+                let mut right = c;
+                while right + 1 < lines[r].len() && lines[r][right + 1].is_ascii_digit() {
+                    right += 1;
                 }
-                match part_numbers.len() {
-                    0 => {}
-                    1 => {}
-                    2 => {
-                        sum += part_numbers[0].value * part_numbers[1].value;
-                    }
-                    _ => panic!("Gear surrounded by more than two part numbers"),
+                let mut part_number = 0;
+                for i in left..=right {
+                    part_number *= 10;
+                    part_number += lines[r][i].to_digit(10).unwrap();
+                }
+                let part_number = PartNumber {
+                    row: r,
+                    first_digit_col: left,
+                    value: part_number,
+                };
+                if !part_numbers
+                    .iter()
+                    .any(|p| p.row == part_number.row && p.first_digit_col == part_number.first_digit_col)
+                {
+                    part_numbers.push(part_number);
+                }
+            }
+            match part_numbers.len() {
+                0 => {}
+                1 => {}
+                2 => {
+                    sum += part_numbers[0].value * part_numbers[1].value;
+                }
+                _ => {
+                    return Err(SolveError::Invalid(format!(
+                        "Gear at {pos:?} is surrounded by more than two part numbers"
+                    )));
                 }
             }
         }
     }
-    sum
+    Ok(sum)
+}
+
+/// Accepts digits, `.` and symbols.
+fn parse_tile(c: char) -> Option<char> {
+    (c.is_ascii_digit() || c.is_ascii_punctuation()).then_some(c)
 }
 
 struct PartNumber {
@@ -131,10 +132,15 @@ static EXAMPLE: &str = "\
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE).to_string(), "4361");
+    assert_eq!(part_1(EXAMPLE).unwrap().to_string(), "4361");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE).to_string(), "467835");
+    assert_eq!(part_2(EXAMPLE).unwrap().to_string(), "467835");
+}
+
+#[test]
+fn invalid_tile() {
+    assert!(matches!(part_1("467..\n..*x.\n"), Err(SolveError::Grid(_))));
 }
@@ -1,10 +1,13 @@
 //! https://adventofcode.com/2023/day/4
 
+use crate::SolveError;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    parser::parse(input)
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse(input)?
         .into_iter()
         .map(Line::matches)
         .map(|matches| {
@@ -17,13 +20,13 @@ pub fn part_1(input: &str) -> impl std::fmt::Display {
             }
             points
         })
-        .sum::<usize>()
+        .sum::<usize>())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
     let mut sum = 0;
     let mut copies = VecDeque::new();
-    for line in parser::parse(input) {
+    for line in parser::parse(input)? {
         let instances = 1 + copies.pop_front().unwrap_or(0);
         sum += instances;
         let matches = line.matches();
@@ -34,7 +37,7 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
             *entry += instances;
         }
     }
-    sum
+    Ok(sum)
 }
 
 struct Line {
@@ -53,10 +56,9 @@ impl Line {
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse(s: &str) -> Vec<Line> {
-        s.lines().map(|line| all_consuming(parse_line)(line).expect_or_report(line).1).collect()
+    pub(super) fn parse(s: &str) -> Result<Vec<Line>, SolveError> {
+        parse_lines(s, parse_line)
     }
 
     fn parse_line(s: &str) -> IResult<&str, Line> {
@@ -88,10 +90,10 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE).to_string(), "13");
+    assert_eq!(part_1(EXAMPLE).unwrap().to_string(), "13");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE).to_string(), "30");
+    assert_eq!(part_2(EXAMPLE).unwrap().to_string(), "30");
 }
@@ -0,0 +1,101 @@
+//! https://adventofcode.com/2023/day/6
+
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let Input { times, distances } = parser::parse(input)?;
+    Ok(times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, record)| ways_to_win(time, record))
+        .product::<u64>())
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let Input { times, distances } = parser::parse(input)?;
+
+    // The spaces between numbers are bad kerning, there is only a single race.
+    let concat = |numbers: Vec<u64>| {
+        let digits = numbers.iter().map(u64::to_string).collect::<String>();
+        digits.parse::<u64>().map_err(|_| SolveError::Invalid(format!("{digits} is too large")))
+    };
+    Ok(ways_to_win(concat(times)?, concat(distances)?))
+}
+
+/// Returns the number of ways to hold the button such that the boat travels further than `record`.
+///
+/// Holding the button for `h` milliseconds travels `h * (time - h)`, so the winning hold times lie strictly between the roots of
+/// `h^2 - time * h + record = 0`.
+fn ways_to_win(time: u64, record: u64) -> u64 {
+    let travels = |hold: u64| u128::from(hold) * u128::from(time - hold);
+    let record_distance = u128::from(record);
+
+    let discriminant = (time as f64).powi(2) - 4.0 * record as f64;
+    if discriminant < 0.0 {
+        return 0;
+    }
+    let midpoint = time as f64 / 2.0;
+    let half_width = discriminant.sqrt() / 2.0;
+
+    // Floating point estimates of the roots, nudged to the first and last winning hold times.
+    let mut lowest = (midpoint - half_width).max(0.0).floor() as u64;
+    while lowest <= time && travels(lowest) <= record_distance {
+        lowest += 1;
+    }
+    let mut highest = ((midpoint + half_width).ceil() as u64).min(time);
+    while highest > lowest && travels(highest) <= record_distance {
+        highest -= 1;
+    }
+
+    if lowest > highest { 0 } else { highest - lowest + 1 }
+}
+
+struct Input {
+    times: Vec<u64>,
+    distances: Vec<u64>,
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Input, SolveError> {
+        parse_all(s, parse_input)
+    }
+
+    fn parse_input(s: &str) -> IResult<&str, Input> {
+        let (s, times) = preceded(pair(tag("Time:"), space1), separated_list1(space1, u64))(s)?;
+        let (s, _) = line_ending(s)?;
+        let (s, distances) = preceded(pair(tag("Distance:"), space1), separated_list1(space1, u64))(s)?;
+        let (s, _) = opt(line_ending)(s)?;
+        Ok((s, Input { times, distances }))
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+Time:      7  15   30
+Distance:  9  40  200
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "288");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "71503");
+}
+
+#[test]
+fn part_2_too_large() {
+    assert!(matches!(
+        part_2("Time: 12345678901 23456789012\nDistance: 1 2\n"),
+        Err(SolveError::Invalid(_))
+    ));
+}
@@ -0,0 +1,136 @@
+//! https://adventofcode.com/2023/day/7
+
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(total_winnings(parser::parse(input)?, false))
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(total_winnings(parser::parse(input)?, true))
+}
+
+fn total_winnings(hands: Vec<Hand>, jokers: bool) -> usize {
+    let mut hands: Vec<(HandType, [u8; 5], usize)> = hands
+        .into_iter()
+        .map(|hand| {
+            let strengths = hand.cards.map(|card| card.strength(jokers));
+            (hand.hand_type(jokers), strengths, hand.bid)
+        })
+        .collect();
+    hands.sort();
+
+    hands.into_iter().enumerate().map(|(idx, (_, _, bid))| (idx + 1) * bid).sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Card(char);
+impl Card {
+    /// Relative strength of the card.
+    ///
+    /// If `jokers` is set, `J` cards are jokers, the weakest individual cards.
+    fn strength(&self, jokers: bool) -> u8 {
+        match self.0 {
+            'J' if jokers => 1,
+            '2'..='9' => self.0.to_digit(10).unwrap() as u8,
+            'T' => 10,
+            'J' => 11,
+            'Q' => 12,
+            'K' => 13,
+            'A' => 14,
+            _ => panic!(),
+        }
+    }
+}
+
+struct Hand {
+    cards: [Card; 5],
+    bid: usize,
+}
+impl Hand {
+    /// If `jokers` is set, `J` cards act like whatever card would make the strongest hand type.
+    fn hand_type(&self, jokers: bool) -> HandType {
+        let mut counts: Vec<(Card, usize)> = vec![];
+        let mut n_jokers = 0;
+        for card in self.cards {
+            if jokers && card.0 == 'J' {
+                n_jokers += 1;
+            } else if let Some((_, n)) = counts.iter_mut().find(|(c, _)| *c == card) {
+                *n += 1;
+            } else {
+                counts.push((card, 1));
+            }
+        }
+
+        let mut counts: Vec<usize> = counts.into_iter().map(|(_, n)| n).collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Jokers always do best by joining the largest group.
+        match counts.first_mut() {
+            Some(n) => *n += n_jokers,
+            None => counts.push(n_jokers),
+        }
+
+        match counts[..] {
+            [5] => HandType::FiveOfAKind,
+            [4, 1] => HandType::FourOfAKind,
+            [3, 2] => HandType::FullHouse,
+            [3, 1, 1] => HandType::ThreeOfAKind,
+            [2, 2, 1] => HandType::TwoPair,
+            [2, 1, 1, 1] => HandType::OnePair,
+            [1, 1, 1, 1, 1] => HandType::HighCard,
+            _ => unreachable!(),
+        }
+    }
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Hand>, SolveError> {
+        parse_lines(s, parse_line)
+    }
+
+    fn parse_line(s: &str) -> IResult<&str, Hand> {
+        let (s, cards) = count(map(one_of("23456789TJQKA"), Card), 5)(s)?;
+        let (s, _) = space1(s)?;
+        let (s, bid) = parse_usize(s)?;
+        let cards = cards.try_into().unwrap();
+        Ok((s, Hand { cards, bid }))
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "6440");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "5905");
+}
@@ -1,9 +1,12 @@
 //! https://adventofcode.com/2023/day/8
 
+use crate::SolveError;
 use std::collections::HashMap;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse(input);
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse(input)?;
     let map = node_map(input.nodes);
 
     let mut node = map.get("AAA").unwrap();
@@ -21,11 +24,11 @@ pub fn part_1(input: &str) -> impl std::fmt::Display {
         steps += 1;
     }
 
-    steps
+    Ok(steps)
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse(input);
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse(input)?;
     let map = node_map(input.nodes);
 
     let mut cycles: Vec<Cycle> = Vec::new();
@@ -79,18 +82,18 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
             cycle_len,
         };
 
-        if cycle.cycle_len % cycle.steps_to_enter_cycle != 0 {
+        if !cycle.cycle_len.is_multiple_of(cycle.steps_to_enter_cycle) {
             todo!("Broken assumption: It's no good assuming that the cycle length is some integer multiple of the steps to enter the cycle");
         }
 
         cycles.push(cycle);
     }
 
-    cycles
+    Ok(cycles
         .iter()
         .map(|c| c.cycle_len)
         .reduce(|c1, c2| c1 * c2 / (gcd::Gcd::gcd(c1, c2)))
-        .unwrap()
+        .unwrap())
 }
 
 fn node_map(nodes: Vec<Node>) -> HashMap<String, Node> {
@@ -107,6 +110,7 @@ struct Cycle {
     cycle_len: usize,
 }
 
+#[derive(Clone)]
 enum Ins {
     L,
     R,
@@ -126,27 +130,25 @@ struct Node {
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
-
-    pub(super) fn parse(s: &str) -> Input {
-        let mut lines = s.lines();
-        let instructions = lines
-            .next()
-            .unwrap()
-            .chars()
-            .map(|c| match c {
-                'R' => Ins::R,
-                'L' => Ins::L,
-                _ => panic!(),
-            })
-            .collect();
+
+    pub(super) fn parse(s: &str) -> Result<Input, SolveError> {
+        parse_all(s, parse_inner)
+    }
+
+    fn parse_inner(s: &str) -> IResult<&str, Input> {
+        let (s, instructions) = many1(parse_ins)(s)?;
+        let (s, _) = line_ending(s)?;
 
         // Blank line
-        lines.next();
+        let (s, _) = line_ending(s)?;
+
+        let (s, nodes) = many1(terminated(parse_node_line, line_ending))(s)?;
 
-        let nodes = lines.map(|line| all_consuming(parse_node_line)(line).expect_or_report(line).1).collect();
+        Ok((s, Input { instructions, nodes }))
+    }
 
-        Input { instructions, nodes }
+    fn parse_ins(s: &str) -> IResult<&str, Ins> {
+        alt((value(Ins::R, char('R')), value(Ins::L, char('L'))))(s)
     }
 
     fn parse_node_line(s: &str) -> IResult<&str, Node> {
@@ -205,15 +207,15 @@ XXX = (XXX, XXX)
 
 #[test]
 fn part_1_example_1() {
-    assert_eq!(part_1(EXAMPLE_1).to_string(), "2");
+    assert_eq!(part_1(EXAMPLE_1).unwrap().to_string(), "2");
 }
 
 #[test]
 fn part_1_example_2() {
-    assert_eq!(part_1(EXAMPLE_2).to_string(), "6");
+    assert_eq!(part_1(EXAMPLE_2).unwrap().to_string(), "6");
 }
 
 #[test]
 fn part_2_example_3() {
-    assert_eq!(part_2(EXAMPLE_3).to_string(), "6");
+    assert_eq!(part_2(EXAMPLE_3).unwrap().to_string(), "6");
 }
@@ -0,0 +1,63 @@
+//! https://adventofcode.com/2023/day/9
+
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse(input)?.iter().map(|history| extrapolate(history)).sum::<i64>())
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse(input)?
+        .into_iter()
+        .map(|mut history| {
+            history.reverse();
+            extrapolate(&history)
+        })
+        .sum::<i64>())
+}
+
+/// Returns the next value of the sequence.
+///
+/// The next value is the sum of the last values of the sequence and each of its successive difference sequences.
+fn extrapolate(history: &[i64]) -> i64 {
+    let mut next = 0;
+    let mut sequence = history.to_vec();
+    while sequence.iter().any(|&v| v != 0) {
+        next += sequence.last().unwrap();
+        sequence = sequence.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    next
+}
+
+mod parser {
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Vec<i64>>, SolveError> {
+        parse_lines(s, parse_line)
+    }
+
+    fn parse_line(s: &str) -> IResult<&str, Vec<i64>> {
+        separated_list1(space1, i64)(s)
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "114");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "2");
+}
@@ -0,0 +1,122 @@
+//! https://adventofcode.com/2023/day/11
+
+use crate::lib::grid::Pos;
+use crate::SolveError;
+use std::collections::HashSet;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    solve(input, 2)
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    solve(input, 1_000_000)
+}
+
+/// Returns the sum of the shortest paths between every pair of galaxies,
+/// after every empty row and column has been replaced by `expansion` empty rows or columns.
+pub fn solve(input: &str, expansion: isize) -> Result<isize, SolveError> {
+    let image = parser::parse(input)?;
+    if image.iter().any(|line| line.len() != image[0].len()) {
+        return Err(SolveError::Invalid("The rows of the image have different lengths".to_string()));
+    }
+    let galaxies: Vec<Pos> = image
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.iter()
+                .enumerate()
+                .filter(|(_col, pixel)| matches!(pixel, Pixel::Galaxy))
+                .map(move |(col, _pixel)| Pos(row as isize, col as isize))
+        })
+        .collect();
+
+    let n_rows = image.len();
+    let n_cols = image.first().map_or(0, Vec::len);
+    let empty_rows_before = empty_before(n_rows, galaxies.iter().map(|g| g.row()).collect());
+    let empty_cols_before = empty_before(n_cols, galaxies.iter().map(|g| g.col()).collect());
+
+    let expanded: Vec<Pos> = galaxies
+        .iter()
+        .map(|&Pos(row, col)| {
+            Pos(
+                row + empty_rows_before[row as usize] * (expansion - 1),
+                col + empty_cols_before[col as usize] * (expansion - 1),
+            )
+        })
+        .collect();
+
+    let mut sum = 0;
+    for (idx, a) in expanded.iter().enumerate() {
+        for b in &expanded[idx + 1..] {
+            sum += a.manhattan_distance(*b);
+        }
+    }
+    Ok(sum)
+}
+
+/// Returns the number of empty rows (or columns) before each of the `n` rows (or columns).
+fn empty_before(n: usize, occupied: HashSet<isize>) -> Vec<isize> {
+    let mut empty = 0;
+    (0..n as isize)
+        .map(|idx| {
+            let before = empty;
+            if !occupied.contains(&idx) {
+                empty += 1;
+            }
+            before
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pixel {
+    Empty,
+    Galaxy,
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Vec<Pixel>>, SolveError> {
+        parse_lines(s, parse_line)
+    }
+
+    fn parse_line(s: &str) -> IResult<&str, Vec<Pixel>> {
+        many1(alt((value(Pixel::Empty, char('.')), value(Pixel::Galaxy, char('#')))))(s)
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "374");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(solve(example(), 10).unwrap(), 1030);
+    assert_eq!(solve(example(), 100).unwrap(), 8410);
+}
+
+#[test]
+fn ragged_rows() {
+    assert!(matches!(part_1("#..\n..#.\n"), Err(SolveError::Invalid(_))));
+}
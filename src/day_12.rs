@@ -1,19 +1,22 @@
 //! https://adventofcode.com/2023/day/12
 
 use crate::my_nom_prelude::*;
+use crate::SolveError;
 use rayon::iter::IntoParallelIterator as _;
 use rayon::iter::ParallelIterator as _;
 use std::collections::HashMap;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    parser::parse(input)
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse(input)?
         .into_par_iter()
         .map(|row| valid_arrangements(&row.springs, &row.groups, &mut HashMap::new()))
-        .sum::<u64>()
+        .sum::<u64>())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    parser::parse(input)
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok(parser::parse(input)?
         .into_par_iter()
         .map(|row| {
             let mut springs = vec![];
@@ -28,7 +31,7 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
             Row { springs, groups }
         })
         .map(|row| valid_arrangements(&row.springs, &row.groups, &mut HashMap::new()))
-        .sum::<u64>()
+        .sum::<u64>())
 }
 
 /// Contiguous group of damaged springs
@@ -104,10 +107,9 @@ fn valid_arrangements<'a>(
 
 mod parser {
     use super::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse(s: &str) -> Vec<Row> {
-        s.lines().map(|line| all_consuming(parse_line)(line).expect_or_report(line).1).collect()
+    pub(super) fn parse(s: &str) -> Result<Vec<Row>, SolveError> {
+        parse_lines(s, parse_line)
     }
 
     fn parse_line(s: &str) -> IResult<&str, Row> {
@@ -135,10 +137,10 @@ static EXAMPLE: &str = "\
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE).to_string(), "21");
+    assert_eq!(part_1(EXAMPLE).unwrap().to_string(), "21");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE).to_string(), "525152");
+    assert_eq!(part_2(EXAMPLE).unwrap().to_string(), "525152");
 }
@@ -0,0 +1,133 @@
+//! https://adventofcode.com/2023/day/13
+
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    parser::parse(input)?
+        .iter()
+        .map(|pattern| summarize(pattern, 0))
+        .sum::<Result<usize, _>>()
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    parser::parse(input)?
+        .iter()
+        .map(|pattern| summarize(pattern, 1))
+        .sum::<Result<usize, _>>()
+}
+
+/// Returns the number of columns left of the line of reflection, or 100 times the number of rows above it.
+///
+/// The line of reflection is the one where exactly `smudges` tiles differ from their mirror image.
+fn summarize(pattern: &Pattern, smudges: usize) -> Result<usize, SolveError> {
+    if let Some(rows_above) = reflection(&pattern.rows, smudges) {
+        return Ok(100 * rows_above);
+    }
+    if let Some(cols_left) = reflection(&transpose(&pattern.rows), smudges) {
+        return Ok(cols_left);
+    }
+    Err(SolveError::Invalid("No line of reflection found".to_string()))
+}
+
+/// Returns the number of rows above a horizontal line of reflection, if any.
+fn reflection(rows: &[Vec<Tile>], smudges: usize) -> Option<usize> {
+    (1..rows.len()).find(|&split| {
+        let (above, below) = rows.split_at(split);
+        let differences: usize = above
+            .iter()
+            .rev()
+            .zip(below)
+            .map(|(a, b)| a.iter().zip(b).filter(|(a, b)| a != b).count())
+            .sum();
+        differences == smudges
+    })
+}
+
+fn transpose(rows: &[Vec<Tile>]) -> Vec<Vec<Tile>> {
+    let n_cols = rows.first().map_or(0, Vec::len);
+    (0..n_cols).map(|col| rows.iter().map(|row| row[col]).collect()).collect()
+}
+
+struct Pattern {
+    rows: Vec<Vec<Tile>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Ash,
+    Rock,
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Pattern>, SolveError> {
+        let patterns = parse_all(s, parse_patterns)?;
+        for (idx, pattern) in patterns.iter().enumerate() {
+            if pattern.rows.iter().any(|row| row.len() != pattern.rows[0].len()) {
+                return Err(SolveError::Invalid(format!("The rows of pattern {} have different lengths", idx + 1)));
+            }
+        }
+        Ok(patterns)
+    }
+
+    fn parse_patterns(s: &str) -> IResult<&str, Vec<Pattern>> {
+        let (s, patterns) = separated_list1(line_ending, parse_pattern)(s)?;
+        Ok((s, patterns))
+    }
+
+    fn parse_pattern(s: &str) -> IResult<&str, Pattern> {
+        let (s, rows) = separated_list1(line_ending, parse_row)(s)?;
+        let (s, _) = opt(line_ending)(s)?;
+        Ok((s, Pattern { rows }))
+    }
+
+    fn parse_row(s: &str) -> IResult<&str, Vec<Tile>> {
+        many1(alt((value(Tile::Ash, char('.')), value(Tile::Rock, char('#')))))(s)
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "405");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "400");
+}
+
+#[test]
+fn without_final_line_ending() {
+    assert_eq!(part_1(example().trim_end()).unwrap().to_string(), "405");
+}
+
+#[test]
+fn invalid_patterns() {
+    assert!(matches!(part_1("#.\n.#\n"), Err(SolveError::Invalid(_))));
+    assert!(matches!(part_1("#..\n#.\n"), Err(SolveError::Invalid(_))));
+}
@@ -1,38 +1,37 @@
 //! https://adventofcode.com/2023/day/14
 
+use crate::lib::grid;
+use crate::lib::grid::Bounds;
+use crate::lib::grid::Grid;
+use crate::lib::grid::GridTile;
+use crate::lib::grid::Pos;
+use crate::SolveError;
 use std::collections::HashMap;
+use std::ops::IndexMut;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let mut lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    roll_north(&mut lines);
-    total_load(&lines)
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let mut grid = grid::vec_of_vecs::Grid::parse_tiles(input)?;
+    roll_north(&mut grid.view_mut());
+    Ok(total_load(&grid))
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let mut lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let mut grid = grid::vec_of_vecs::Grid::parse_tiles(input)?;
     let mut cache_opt = Some(HashMap::new());
 
     let mut cycle = 1;
     while cycle <= 1000000000 {
-        // Roll north
-        roll_north(&mut lines);
-        lines = rotate_cw(&lines);
-
-        // Roll west
-        roll_north(&mut lines);
-        lines = rotate_cw(&lines);
-
-        // Roll south
-        roll_north(&mut lines);
-        lines = rotate_cw(&lines);
-
-        // Roll east
-        roll_north(&mut lines);
-        lines = rotate_cw(&lines);
+        // Roll north, west, south and east, by rolling north in a view with that side up.
+        roll_north(&mut grid.view_mut());
+        roll_north(&mut grid.view_mut().rotate_cw());
+        roll_north(&mut grid.view_mut().rotate_180());
+        roll_north(&mut grid.view_mut().rotate_ccw());
 
         // Check for repetition
         if let Some(cache) = cache_opt.as_mut() {
-            let key = lines.iter().flat_map(|line| line.iter()).collect::<String>();
+            let key = grid.iter().map(|(_pos, tile)| tile.to_char()).collect::<String>();
             if let Some(cached) = cache.get(&key) {
                 log::info!("Repetition detected after {cycle} cycles");
 
@@ -51,48 +50,37 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
         cycle += 1;
     }
 
-    total_load(&lines)
-}
-
-/// Rotates the grid clockwise
-fn rotate_cw(lines: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let n_rows = lines.len();
-    let n_cols = lines[0].len();
-    let mut new = vec![vec!['?'; n_rows]; n_cols];
-    for row in 0..n_rows {
-        for (col, item) in new.iter_mut().enumerate() {
-            item[n_rows - row - 1] = lines[row][col];
-        }
-    }
-    new
+    Ok(total_load(&grid))
 }
 
-fn roll_north(lines: &mut Vec<Vec<char>>) {
-    let n_rows = lines.len();
-    let n_cols = lines[0].len();
+fn roll_north<G>(grid: &mut G)
+where
+    G: Grid<Tile = Tile> + IndexMut<Pos, Output = Tile>,
+{
+    let Bounds { max_row, max_col, .. } = grid.bounds();
+    let (n_rows, n_cols) = (max_row + 1, max_col + 1);
     for col in 0..n_cols {
         let mut row_idx_1 = 0;
         'outer_while: while row_idx_1 < n_rows {
-            if lines[row_idx_1][col] == '.' {
+            if matches!(grid[Pos(row_idx_1, col)], Tile::Empty) {
                 // Empty space where a round rock can slide to.
                 // Find a round rock.
                 let mut row_idx_2 = row_idx_1 + 1;
                 while row_idx_2 < n_rows {
-                    match lines[row_idx_2][col] {
-                        'O' => {
+                    match grid[Pos(row_idx_2, col)] {
+                        Tile::RoundRock => {
                             // Move the rock
-                            lines[row_idx_1][col] = 'O';
-                            lines[row_idx_2][col] = '.';
+                            grid[Pos(row_idx_1, col)] = Tile::RoundRock;
+                            grid[Pos(row_idx_2, col)] = Tile::Empty;
                             break;
                         }
-                        '#' => {
+                        Tile::CubeRock => {
                             row_idx_1 = row_idx_2 + 1;
                             continue 'outer_while;
                         }
-                        '.' => {
+                        Tile::Empty => {
                             row_idx_2 += 1;
                         }
-                        _ => panic!(),
                     }
                 }
             }
@@ -101,17 +89,21 @@ fn roll_north(lines: &mut Vec<Vec<char>>) {
     }
 }
 
-fn total_load(lines: &Vec<Vec<char>>) -> usize {
-    let n_rows = lines.len();
-    let mut total_load = 0;
-    for (r_idx, row) in lines.iter().enumerate() {
-        for col in row {
-            if *col == 'O' {
-                total_load += n_rows - r_idx;
-            }
-        }
-    }
-    total_load
+fn total_load(grid: &grid::vec_of_vecs::Grid<Tile>) -> usize {
+    grid.iter()
+        .filter(|(_pos, tile)| matches!(tile, Tile::RoundRock))
+        .map(|(pos, _c)| grid.n_rows - pos.row() as usize)
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, GridTile)]
+enum Tile {
+    #[tile('O')]
+    RoundRock,
+    #[tile('#')]
+    CubeRock,
+    #[tile('.')]
+    Empty,
 }
 
 #[cfg(test)]
@@ -130,10 +122,15 @@ O.#..O.#.#
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE).to_string(), "136");
+    assert_eq!(part_1(EXAMPLE).unwrap().to_string(), "136");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE).to_string(), "64");
+    assert_eq!(part_2(EXAMPLE).unwrap().to_string(), "64");
+}
+
+#[test]
+fn invalid_tile() {
+    assert!(matches!(part_1("O.#\n.x.\n"), Err(SolveError::Grid(_))));
 }
@@ -1,14 +1,16 @@
 //! https://adventofcode.com/2023/day/15
 
-use type_toppings::ResultExt as _;
+use crate::SolveError;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
     let steps = split_input_to_steps(input);
-    steps.map(|step| step.chars().map(|c| c as usize).fold(0, hash)).sum::<usize>()
+    Ok(steps.map(|step| step.chars().map(|c| c as usize).fold(0, hash)).sum::<usize>())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let steps = split_input_to_steps(input).map(parse_step);
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let steps = parser::parse(input)?.into_iter().flatten();
     let mut boxes: [Vec<(&str, u8)>; 256] = std::array::from_fn(|_| Vec::new());
 
     for step in steps {
@@ -39,7 +41,7 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
         // println!("");
     }
 
-    focusing_power(&boxes)
+    Ok(focusing_power(&boxes))
 }
 
 fn focusing_power(boxes: &[Vec<(&str, u8)>; 256]) -> usize {
@@ -91,18 +93,20 @@ impl<'a> Step<'a> {
 type Label<'a> = &'a str;
 type Lens = u8;
 
-fn parse_step(step: &str) -> Step<'_> {
+mod parser {
+    use super::*;
     use crate::my_nom_prelude::*;
 
-    let remove = terminated(alpha1, tag("-"));
-    let upsert = separated_pair(alpha1, tag("="), u8);
+    pub(super) fn parse(s: &str) -> Result<Vec<Vec<Step<'_>>>, SolveError> {
+        parse_lines(s, separated_list1(char(','), parse_step))
+    }
 
-    let result: IResult<&str, Step> = all_consuming(alt((
-        map(remove, Step::Remove),
-        map(upsert, |(label, lens)| Step::Upsert(label, lens)),
-    )))(step);
+    fn parse_step(s: &str) -> IResult<&str, Step<'_>> {
+        let remove = terminated(alpha1, tag("-"));
+        let upsert = separated_pair(alpha1, tag("="), u8);
 
-    result.expect_or_report(step).1
+        alt((map(remove, Step::Remove), map(upsert, |(label, lens)| Step::Upsert(label, lens))))(s)
+    }
 }
 
 #[cfg(test)]
@@ -112,10 +116,10 @@ rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(EXAMPLE).to_string(), "1320");
+    assert_eq!(part_1(EXAMPLE).unwrap().to_string(), "1320");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(EXAMPLE).to_string(), "145");
+    assert_eq!(part_2(EXAMPLE).unwrap().to_string(), "145");
 }
@@ -1,22 +1,30 @@
 //! https://adventofcode.com/2023/day/16
 
+use crate::lib::export::Image;
 use crate::lib::grid;
 use crate::lib::grid::Dir;
+use crate::lib::grid::Grid as _;
+use crate::lib::grid::GridTile;
 use crate::lib::grid::Pos;
 use crate::lib::grid::PosDir;
+use crate::ExportError;
+use crate::SolveError;
+use std::path::Path;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let mut grid = grid::vec_of_vecs::Grid::parse_char_grid(input, Tile::new);
+crate::solution!(part_1, part_2; export = export);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let mut grid = parse_grid(input)?;
     let pos_dir = PosDir {
         pos: Pos(0, -1),
         dir: Dir::E,
     };
     propagate_light(&mut grid, pos_dir);
-    grid.into_iter().filter(|(_pos, tile)| tile.is_energized()).count()
+    Ok(grid.into_iter().filter(|(_pos, tile)| tile.is_energized()).count())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let mut grid = grid::vec_of_vecs::Grid::parse_char_grid(input, Tile::new);
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let mut grid = parse_grid(input)?;
     let n_rows = grid.n_rows;
     let n_cols = grid.n_cols;
 
@@ -39,14 +47,30 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
 
     let edges = north_edge.chain(west_edge).chain(south_edge).chain(east_edge);
 
-    edges
+    Ok(edges
         .map(|start| {
             grid.iter_mut().for_each(|(_pos, tile)| tile.clear());
             propagate_light(&mut grid, start);
             grid.iter().filter(|(_pos, tile)| tile.is_energized()).count()
         })
         .max()
-        .unwrap()
+        .unwrap())
+}
+
+/// Draws the tiles energized by the beam of part 1 in yellow, and the mirrors and splitters in grey.
+pub fn export(input: &str, path: &Path) -> Result<(), ExportError> {
+    let mut grid = parse_grid(input).map_err(SolveError::from)?;
+    let pos_dir = PosDir {
+        pos: Pos(0, -1),
+        dir: Dir::E,
+    };
+    propagate_light(&mut grid, pos_dir);
+    let image = Image::from_grid(&grid, |_pos, tile| match tile.optics {
+        _ if tile.is_energized() => [255, 224, 0],
+        Optics::Empty => [0, 0, 0],
+        _ => [96, 96, 96],
+    });
+    Ok(image.scale(4).save(path)?)
 }
 
 fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
@@ -55,11 +79,9 @@ fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
 
     while let Some(mut pos_dir) = pos_dir_stack.pop() {
         pos_dir = pos_dir.step_forward();
-        if !grid.contains_pos(pos_dir.pos) {
+        let Some(tile) = grid.get_mut(pos_dir.pos) else {
             continue;
-        }
-
-        let tile = &mut grid[pos_dir.pos];
+        };
         let energized = match pos_dir.dir {
             Dir::N => &mut tile.visited_while_moving_north,
             Dir::W => &mut tile.visited_while_moving_west,
@@ -72,9 +94,9 @@ fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
             *energized = true;
         }
 
-        match grid[pos_dir.pos].c {
-            '.' => pos_dir_stack.push(pos_dir),
-            '|' => match pos_dir.dir {
+        match tile.optics {
+            Optics::Empty => pos_dir_stack.push(pos_dir),
+            Optics::VerticalSplitter => match pos_dir.dir {
                 Dir::N | Dir::S => {
                     pos_dir_stack.push(pos_dir);
                 }
@@ -83,7 +105,7 @@ fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
                     pos_dir_stack.push(pos_dir.turn_right());
                 }
             },
-            '-' => match pos_dir.dir {
+            Optics::HorizontalSplitter => match pos_dir.dir {
                 Dir::N | Dir::S => {
                     pos_dir_stack.push(pos_dir.turn_left());
                     pos_dir_stack.push(pos_dir.turn_right());
@@ -92,7 +114,7 @@ fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
                     pos_dir_stack.push(pos_dir);
                 }
             },
-            '/' => match pos_dir.dir {
+            Optics::Mirror => match pos_dir.dir {
                 Dir::N | Dir::S => {
                     pos_dir_stack.push(pos_dir.turn_right());
                 }
@@ -100,7 +122,7 @@ fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
                     pos_dir_stack.push(pos_dir.turn_left());
                 }
             },
-            '\\' => match pos_dir.dir {
+            Optics::BackslashMirror => match pos_dir.dir {
                 Dir::N | Dir::S => {
                     pos_dir_stack.push(pos_dir.turn_left());
                 }
@@ -108,23 +130,36 @@ fn propagate_light(grid: &mut grid::vec_of_vecs::Grid<Tile>, pos_dir: PosDir) {
                     pos_dir_stack.push(pos_dir.turn_right());
                 }
             },
-            _ => panic!(),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, GridTile)]
+enum Optics {
+    #[tile('.')]
+    Empty,
+    #[tile('|')]
+    VerticalSplitter,
+    #[tile('-')]
+    HorizontalSplitter,
+    #[tile('/')]
+    Mirror,
+    #[tile('\\')]
+    BackslashMirror,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct Tile {
-    c: char,
+    optics: Optics,
     visited_while_moving_north: bool,
     visited_while_moving_west: bool,
     visited_while_moving_south: bool,
     visited_while_moving_east: bool,
 }
 impl Tile {
-    fn new(c: char) -> Tile {
+    fn new(optics: Optics) -> Tile {
         Tile {
-            c,
+            optics,
             visited_while_moving_north: false,
             visited_while_moving_west: false,
             visited_while_moving_south: false,
@@ -132,7 +167,7 @@ impl Tile {
         }
     }
     fn clear(&mut self) {
-        *self = Tile::new(self.c);
+        *self = Tile::new(self.optics);
     }
     fn is_energized(&self) -> bool {
         self.visited_while_moving_north || self.visited_while_moving_west || self.visited_while_moving_south || self.visited_while_moving_east
@@ -140,10 +175,14 @@ impl Tile {
 }
 impl std::fmt::Display for Tile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.c)
+        write!(f, "{}", self.optics)
     }
 }
 
+fn parse_grid(input: &str) -> Result<grid::vec_of_vecs::Grid<Tile>, grid::vec_of_vecs::ParseError> {
+    grid::vec_of_vecs::Grid::try_parse_char_grid(input, |c| Optics::from_char(c).map(Tile::new))
+}
+
 #[cfg(test)]
 fn example() -> &'static str {
     &r#"
@@ -162,10 +201,10 @@ fn example() -> &'static str {
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(example()).to_string(), "46");
+    assert_eq!(part_1(example()).unwrap().to_string(), "46");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(example()).to_string(), "51");
+    assert_eq!(part_2(example()).unwrap().to_string(), "51");
 }
@@ -4,11 +4,15 @@ use crate::lib::graph;
 use crate::lib::graph::shortest_path::cost::Cost;
 use crate::lib::grid;
 use crate::lib::grid::Dir;
+use crate::lib::grid::Grid as _;
 use crate::lib::grid::Pos;
 use crate::lib::grid::PosDir;
+use crate::SolveError;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let grid = parse_grid(input);
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let grid = parse_grid(input)?;
     let graph = Graph {
         grid,
         ultra_crucibles: false,
@@ -19,11 +23,11 @@ pub fn part_1(input: &str) -> impl std::fmt::Display {
     };
     let end_pos = Pos(graph.grid.n_rows as isize - 1, graph.grid.n_cols as isize - 1);
     let is_end = |node: &Node| node.pos_dir.pos == end_pos;
-    graph::shortest_path::dijkstra_min_heap(&graph, start_node, is_end).unwrap()
+    Ok(graph::shortest_path::dijkstra_min_heap(&graph, start_node, is_end).unwrap())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let grid = parse_grid(input);
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let grid = parse_grid(input)?;
     let graph = Graph { grid, ultra_crucibles: true };
     let start_node = Node {
         pos_dir: PosDir { pos: Pos(0, 0), dir: Dir::E },
@@ -31,7 +35,7 @@ pub fn part_2(input: &str) -> impl std::fmt::Display {
     };
     let end_pos = Pos(graph.grid.n_rows as isize - 1, graph.grid.n_cols as isize - 1);
     let is_end = |node: &Node| node.pos_dir.pos == end_pos && node.sequential_straight_moves >= 4;
-    graph::shortest_path::dijkstra_min_heap(&graph, start_node, is_end).unwrap()
+    Ok(graph::shortest_path::dijkstra_min_heap(&graph, start_node, is_end).unwrap())
 }
 
 struct Graph {
@@ -72,7 +76,7 @@ impl graph::directed::Node<Graph> for Node {
             .map(|pos_dir| pos_dir.step_forward())
             .filter(|pos_dir| {
                 // Out of bounds check
-                graph.grid.contains_pos(pos_dir.pos)
+                graph.grid.contains(pos_dir.pos)
             })
             .map(|pos_dir| {
                 let sequential_straight_moves = if self.pos_dir.dir == pos_dir.dir {
@@ -106,8 +110,8 @@ impl Cost for Edge {
     }
 }
 
-fn parse_grid(input: &str) -> grid::vec_of_vecs::Grid<u8> {
-    grid::vec_of_vecs::Grid::<u8>::parse_char_grid(input, |c| c.to_digit(10).unwrap() as u8)
+fn parse_grid(input: &str) -> Result<grid::vec_of_vecs::Grid<u8>, grid::vec_of_vecs::ParseError> {
+    grid::vec_of_vecs::Grid::<u8>::try_parse_char_grid(input, |c| c.to_digit(10).map(|d| d as u8))
 }
 
 #[cfg(test)]
@@ -142,15 +146,15 @@ fn example_2() -> &'static str {
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(example()).to_string(), "102");
+    assert_eq!(part_1(example()).unwrap().to_string(), "102");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(example()).to_string(), "94");
+    assert_eq!(part_2(example()).unwrap().to_string(), "94");
 }
 
 #[test]
 fn part_2_example_2() {
-    assert_eq!(part_2(example_2()).to_string(), "71");
+    assert_eq!(part_2(example_2()).unwrap().to_string(), "71");
 }
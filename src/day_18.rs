@@ -1,208 +1,100 @@
 //! https://adventofcode.com/2023/day/18
 
+use crate::lib::export::Svg;
+use crate::lib::geometry;
 use crate::lib::grid::Dir;
 use crate::lib::grid::Pos;
-use crate::lib::grid::RelDir;
-use std::cmp::max;
-use std::cmp::min;
-use std::collections::VecDeque;
-
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse_part_1(input);
-    solve(input)
+use crate::ExportError;
+use crate::SolveError;
+use std::path::Path;
+
+crate::solution!(part_1, part_2; export = export);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse_part_1(input)?;
+    validate(&input)?;
+    Ok(solve(input))
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse_part_2(input)?;
+    validate(&input)?;
+    Ok(solve(input))
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse_part_2(input);
-    solve(input)
+/// Draws the trench dug by the plan of part 1 as an SVG.
+pub fn export(input: &str, path: &Path) -> Result<(), ExportError> {
+    let input = parser::parse_part_1(input)?;
+    let vertices = geometry::walk(Pos(0, 0), input.iter().map(|line| (line.dir, line.steps)));
+    Ok(Svg::default().polyline(vertices, "black").save(path)?)
 }
 
+/// Returns the size of the lagoon dug by the plan, which must be valid according to [`validate`].
 fn solve(input: Vec<InputLine>) -> impl std::fmt::Display {
-    let mut points = Vec::with_capacity(input.len());
-    {
-        let mut pos = Pos(0, 0);
-        points.push(Some(pos));
-        for line in input.iter() {
-            pos = pos.steps(line.steps, line.dir);
-            points.push(Some(pos));
-        }
-        assert_eq!(pos, Pos(0, 0), "expect we'll end up back where we started");
-    }
+    // The lagoon is the trench itself, which runs through the boundary points, and everything inside it.
+    let vertices = geometry::walk(Pos(0, 0), input.iter().map(|line| (line.dir, line.steps)));
+    geometry::boundary_points(&vertices) + geometry::interior_points(&vertices)
+}
 
-    let mut area = 0;
-
-    let mut hist_pos = VecDeque::<(Pos, usize)>::new();
-    let mut n_points = points.len();
-    'outer_outer: for buff_out in [true, false] {
-        'outer: loop {
-            // // Debug
-            // {
-            //     println!("################");
-            //     let mut g = crate::lib::grid::hash_map::Grid::new();
-            //     for p in points.iter().flatten() {
-            //         g.insert(*p, ());
-            //     }
-            //     g.dbg(|t| if t.is_some() { '#' } else { '·' });
-
-            //     for p in points.iter().flatten() {
-            //         println!("({},{})", p.0, p.1);
-            //     }
-            //     println!("area: {area}");
-            // }
-
-            if n_points <= 4 {
-                assert_eq!(n_points, 4);
-
-                points.retain(|p| p.is_some());
-                assert_eq!(points.len(), 4);
-
-                let c0 = points[0].unwrap();
-                let c1 = points[1].unwrap();
-                let c2 = points[2].unwrap();
-                let c3 = points[3].unwrap();
-
-                let a = ((c0.row() - c2.row()).abs() + 1) * ((c0.col() - c2.col()).abs() + 1);
-                area += a;
-
-                continue 'outer_outer;
-            }
+/// Why a dig plan does not describe a lagoon.
+///
+/// Instructions are numbered from 1, like the lines of the input.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+enum PlanError {
+    #[display(fmt = "The trench ends at {:?}, not where it started", end)]
+    NotClosed { end: Pos },
 
-            // Clean up sparse vec
-            if points.len() > 2 * n_points {
-                points.retain(|p| p.is_some());
-            }
+    #[display(fmt = "The trench dug by instruction {} runs into the trench dug by instruction {}", second, first)]
+    Intersection { first: usize, second: usize },
+}
+impl From<PlanError> for SolveError {
+    fn from(err: PlanError) -> Self {
+        SolveError::Invalid(err.to_string())
+    }
+}
 
-            // Iterate through the points
-            hist_pos.clear();
-            for idx in (0..points.len()).chain(0..4) {
-                let maybe_pos = &points[idx];
-                let &Some(pos) = maybe_pos else {
-                    continue;
-                };
-
-                let mut it = hist_pos.iter().copied();
-                let pos_1 = it.next();
-                let pos_2 = it.next();
-                let pos_3 = it.next();
-                let pos_4 = it.next();
-                let pos_5 = it.next();
-
-                // Remove sequential duplicate points
-                if let Some((pos_1, pos_1_idx)) = pos_1 {
-                    if pos == pos_1 {
-                        points[pos_1_idx] = None;
-                        n_points -= 1;
-                        continue 'outer;
-                    }
-                }
-
-                // Look for three points that lie on a straight line, and remove the middle point
-                match (pos_1, pos_2) {
-                    (Some((pos_1, pos_1_idx)), Some((pos_2, _pos_2_idx))) => {
-                        let is_on_straight_line =
-                            (pos.row() == pos_1.row() && pos_1.row() == pos_2.row()) || (pos.col() == pos_1.col() && pos_1.col() == pos_2.col());
-                        if is_on_straight_line {
-                            points[pos_1_idx] = None;
-                            n_points -= 1;
-                            continue 'outer;
-                        }
-                    }
-                    _ => {}
-                }
-
-                // Look for alcoves to collapse
-                'alcove: {
-                    match (pos_1, pos_2, pos_3, pos_4, pos_5) {
-                        (
-                            Some((pos_1, pos_1_idx)),
-                            Some((pos_2, pos_2_idx)),
-                            Some((pos_3, pos_3_idx)),
-                            Some((pos_4, pos_4_idx)),
-                            Some((pos_5, pos_5_idx)),
-                        ) => {
-                            let dist_1 = pos.manhattan_distance(pos_1);
-                            let dist_2 = pos_1.manhattan_distance(pos_2);
-                            let dist_3 = pos_2.manhattan_distance(pos_3);
-                            let dist_4 = pos_3.manhattan_distance(pos_4);
-                            let dist_5 = pos_4.manhattan_distance(pos_5);
-
-                            let dir_1 = Dir::from_positions(pos_1, pos).unwrap();
-                            let dir_2 = Dir::from_positions(pos_2, pos_1).unwrap();
-                            let dir_3 = Dir::from_positions(pos_3, pos_2).unwrap();
-                            let dir_4 = Dir::from_positions(pos_4, pos_3).unwrap();
-                            let dir_5 = Dir::from_positions(pos_5, pos_4).unwrap();
-
-                            let turn_1 = RelDir::from_dirs(dir_1, dir_2);
-                            let turn_2 = RelDir::from_dirs(dir_2, dir_3);
-                            let turn_3 = RelDir::from_dirs(dir_3, dir_4);
-                            let turn_4 = RelDir::from_dirs(dir_4, dir_5);
-
-                            assert!(matches!(turn_1, RelDir::Left | RelDir::Right));
-                            assert!(matches!(turn_2, RelDir::Left | RelDir::Right));
-                            assert!(matches!(turn_3, RelDir::Left | RelDir::Right));
-                            assert!(matches!(turn_4, RelDir::Left | RelDir::Right));
-
-                            if turn_2 == turn_3 {
-                                match (buff_out, turn_2, turn_3) {
-                                    (false, RelDir::Left, RelDir::Left) => {
-                                        // curves outward
-                                    }
-                                    (true, RelDir::Right, RelDir::Right) => {
-                                        // curves inward
-                                    }
-                                    _ => {
-                                        break 'alcove;
-                                    }
-                                };
-
-                                let dim_1 = min(dist_2, dist_4);
-                                let dim_2 = pos_2.manhattan_distance(pos_3);
-
-                                // can be e.g. LRRL.
-                                let b_1 = turn_1 == turn_4 && turn_1 != turn_2;
-
-                                // can be e.g. ?RRL.
-                                let b_2 = turn_1 != turn_2 && dist_2 < dist_4;
-
-                                // can be e.g. LRR?.
-                                let b_3 = turn_4 != turn_2 && dist_2 > dist_4;
-
-                                if b_1 || b_2 || b_3 {
-                                    let pos_2 = points[pos_2_idx].as_mut().unwrap();
-                                    *pos_2 = pos_2.steps(dim_1, dir_2);
-
-                                    let pos_3 = points[pos_3_idx].as_mut().unwrap();
-                                    *pos_3 = pos_3.steps(dim_1, dir_2);
-
-                                    let a = if buff_out {
-                                        -1 * (dim_1) * (dim_2 + 1) + dim_1 * 2
-                                    } else {
-                                        (dim_1) * (dim_2 + 1)
-                                    };
-                                    area += a;
-
-                                    continue 'outer;
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-
-                if hist_pos.len() > 5 {
-                    hist_pos.pop_back();
-                }
-                hist_pos.push_front((pos, idx));
-            }
+/// Checks that the plan digs a single trench that ends where it started, and never crosses or touches itself.
+///
+/// Reports the first instruction that runs into the trench dug by an earlier instruction, along with that earlier instruction.
+/// Instructions to dig zero steps dig nothing, and are skipped.
+fn validate(plan: &[InputLine]) -> Result<(), PlanError> {
+    // The trench dug by each instruction, as its (1-based) number and the corners of its (one tile wide) bounding box.
+    let mut segments = Vec::with_capacity(plan.len());
+    let mut pos = Pos(0, 0);
+    for (i, line) in plan.iter().enumerate() {
+        let next = pos.steps(line.steps, line.dir);
+        if next != pos {
+            segments.push((i + 1, pos.min(next), pos.max(next)));
+        }
+        pos = next;
+    }
+    if pos != Pos(0, 0) {
+        return Err(PlanError::NotClosed { end: pos });
+    }
 
-            // Didn't find anything
-            continue 'outer_outer;
+    // Number of tiles two segments have in common.
+    let overlap = |(_, a_min, a_max): (usize, Pos, Pos), (_, b_min, b_max): (usize, Pos, Pos)| {
+        let rows = a_max.row().min(b_max.row()) - a_min.row().max(b_min.row()) + 1;
+        let cols = a_max.col().min(b_max.col()) - a_min.col().max(b_min.col()) + 1;
+        rows.max(0) * cols.max(0)
+    };
+
+    let n = segments.len();
+    for second in 0..n {
+        for first in 0..second {
+            // Consecutive instructions share the corner between them, and the last instruction ends where the first started.
+            let adjacent = second == first + 1 || (first == 0 && second == n - 1);
+            let allowed = if adjacent { 1 } else { 0 };
+            if overlap(segments[first], segments[second]) > allowed {
+                return Err(PlanError::Intersection {
+                    first: segments[first].0,
+                    second: segments[second].0,
+                });
+            }
         }
     }
 
-    // let path_length: isize = input.iter().map(|l| l.steps).sum();
-    // area + path_length
-    area
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -214,29 +106,23 @@ struct InputLine {
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse_part_1(s: &str) -> Vec<InputLine> {
-        s.lines()
-            .map(|line| all_consuming(parse_line_part_1)(line).expect_or_report(line).1)
-            .collect()
+    pub(super) fn parse_part_1(s: &str) -> Result<Vec<InputLine>, SolveError> {
+        parse_lines(s, parse_line_part_1)
     }
 
-    pub(super) fn parse_part_2(s: &str) -> Vec<InputLine> {
-        s.lines()
-            .map(|line| all_consuming(parse_line_part_2)(line).expect_or_report(line).1)
-            .collect()
+    pub(super) fn parse_part_2(s: &str) -> Result<Vec<InputLine>, SolveError> {
+        parse_lines(s, parse_line_part_2)
     }
 
     fn parse_line_part_1(s: &str) -> IResult<&str, InputLine> {
-        let (s, dir) = anychar(s)?;
-        let dir = match dir {
-            'U' => Dir::N,
-            'L' => Dir::W,
-            'D' => Dir::S,
-            'R' => Dir::E,
-            _ => panic!(),
-        };
+        let (s, dir) = map_opt(anychar, |c| match c {
+            'U' => Some(Dir::N),
+            'L' => Some(Dir::W),
+            'D' => Some(Dir::S),
+            'R' => Some(Dir::E),
+            _ => None,
+        })(s)?;
         let (s, _) = space1(s)?;
         let (s, steps) = parse_isize(s)?;
         let (s, _) = space1(s)?;
@@ -253,7 +139,7 @@ mod parser {
         let (s, _) = space1(s)?;
 
         let (s, _) = tag("(#")(s)?;
-        let (s, steps) = map_res(recognize(count(satisfy(|c| c.is_digit(16)), 5)), |h| isize::from_str_radix(h, 16))(s)?;
+        let (s, steps) = map_res(recognize(count(satisfy(|c| c.is_ascii_hexdigit()), 5)), |h| isize::from_str_radix(h, 16))(s)?;
         let (s, dir) = map_opt(anychar, |c| match c {
             '0' => Some(Dir::E),
             '1' => Some(Dir::S),
@@ -290,22 +176,53 @@ U 2 (#7a21e3)
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(example()).to_string(), "62");
+    assert_eq!(part_1(example()).unwrap().to_string(), "62");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(example()).to_string(), "952408144115");
+    assert_eq!(part_2(example()).unwrap().to_string(), "952408144115");
+}
+
+#[test]
+fn validate_plan() {
+    let plan = |s: &str| parser::parse_part_1(s).unwrap();
+
+    assert_eq!(validate(&plan(example())), Ok(()));
+    assert_eq!(
+        validate(&plan("R 2 (#000000)\nD 2 (#000000)\nL 2 (#000000)\n")),
+        Err(PlanError::NotClosed { end: Pos(2, 0) })
+    );
+
+    // Crosses the first instruction
+    let crossing = "R 4 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 4 (#000000)\nL 2 (#000000)\nD 2 (#000000)\n";
+    assert_eq!(validate(&plan(crossing)), Err(PlanError::Intersection { first: 1, second: 4 }));
+
+    // Turns back on itself
+    let reversing = "R 2 (#000000)\nL 1 (#000000)\nD 1 (#000000)\nL 1 (#000000)\nU 1 (#000000)\n";
+    assert_eq!(validate(&plan(reversing)), Err(PlanError::Intersection { first: 1, second: 2 }));
+
+    // Touches a corner of the trench, in a figure eight
+    let touching = "R 2 (#000000)\nD 2 (#000000)\nR 2 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)\n";
+    assert_eq!(validate(&plan(touching)), Err(PlanError::Intersection { first: 2, second: 6 }));
+
+    // Digs nothing between two instructions, and crosses itself later on
+    let idle = "R 2 (#000000)\nD 0 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 0 (#000000)\nU 2 (#000000)\n";
+    assert_eq!(validate(&plan(idle)), Ok(()));
+    let idle_crossing = format!("R 0 (#000000)\n{crossing}");
+    assert_eq!(validate(&plan(&idle_crossing)), Err(PlanError::Intersection { first: 2, second: 5 }));
+
+    assert!(matches!(part_1(crossing), Err(SolveError::Invalid(_))));
 }
 
-// #[test]
-// fn part_1_real() {
-//     let input = crate::read_input(format!("day_18.txt")).unwrap();
-//     assert_eq!(part_1(&input).to_string(), "40545");
-// }
+#[test]
+fn part_1_real() {
+    let input = crate::inputs::cached_for_test(18);
+    assert_eq!(part_1(&input).unwrap().to_string(), "40745");
+}
 
 #[test]
 fn part_2_real() {
-    let input = crate::read_input(format!("day_18.txt")).unwrap();
-    assert_eq!(part_2(&input).to_string(), "90111113594927");
+    let input = crate::inputs::cached_for_test(18);
+    assert_eq!(part_2(&input).unwrap().to_string(), "90111113594927");
 }
@@ -1,12 +1,15 @@
 //! https://adventofcode.com/2023/day/19
 
+use crate::lib::interval::Interval;
+use crate::SolveError;
 use std::collections::HashMap;
-use std::ops::Range;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse(input);
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse(input)?;
     let workflow_map: WorkflowMap = HashMap::from_iter(input.workflows.iter().map(|w| (w.name, w)));
-    input
+    Ok(input
         .parts
         .iter()
         .filter(|part| {
@@ -28,23 +31,23 @@ pub fn part_1(input: &str) -> impl std::fmt::Display {
             }
         })
         .map(|part| part.x + part.a + part.s + part.m)
-        .sum::<isize>()
+        .sum::<isize>())
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse(input);
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse(input)?;
     let workflow_map: WorkflowMap = HashMap::from_iter(input.workflows.iter().map(|w| (w.name, w)));
 
     let ranges = Ranges {
-        x: 1..4001,
-        m: 1..4001,
-        s: 1..4001,
-        a: 1..4001,
+        x: Interval::new(1, 4001),
+        m: Interval::new(1, 4001),
+        s: Interval::new(1, 4001),
+        a: Interval::new(1, 4001),
     };
 
     let workflow = workflow_map["in"];
     let rule_idx = 0;
-    accepted_configurations(&workflow_map, workflow, rule_idx, ranges)
+    Ok(accepted_configurations(&workflow_map, workflow, rule_idx, ranges))
 }
 
 fn accepted_configurations(workflow_map: &WorkflowMap<'_>, workflow: &Workflow, rule_idx: usize, ranges: Ranges) -> usize {
@@ -69,23 +72,20 @@ fn accepted_configurations(workflow_map: &WorkflowMap<'_>, workflow: &Workflow,
     };
 
     // Split range
-    let r = &ranges[rule.category];
-    let (r1, r2) = match rule.operator {
-        '<' => (r.start..rule.value, rule.value..r.end),
-        '>' => (r.start..(rule.value + 1), (rule.value + 1)..r.end),
+    let r = ranges[rule.category];
+    let (r_passed, r_failed) = match rule.operator {
+        '<' => r.split_at(rule.value),
+        '>' => {
+            let (below, above) = r.split_at(rule.value + 1);
+            (above, below)
+        }
         _ => panic!(),
     };
 
-    let mut ranges1 = ranges.clone();
-    let mut ranges2 = ranges.clone();
-    *&mut ranges1[rule.category] = r1;
-    *&mut ranges2[rule.category] = r2;
-
-    let (passed, failed) = match rule.operator {
-        '<' => (ranges1, ranges2),
-        '>' => (ranges2, ranges1),
-        _ => panic!(),
-    };
+    let mut passed = ranges.clone();
+    let mut failed = ranges;
+    passed[rule.category] = r_passed;
+    failed[rule.category] = r_failed;
 
     let mut total = 0;
 
@@ -129,10 +129,10 @@ type WorkflowMap<'a> = HashMap<&'a str, &'a Workflow<'a>>;
 
 #[derive(Debug, Clone)]
 struct Ranges {
-    x: Range<isize>,
-    m: Range<isize>,
-    a: Range<isize>,
-    s: Range<isize>,
+    x: Interval,
+    m: Interval,
+    a: Interval,
+    s: Interval,
 }
 impl Ranges {
     fn combinations(&self) -> usize {
@@ -140,7 +140,7 @@ impl Ranges {
     }
 }
 impl std::ops::Index<char> for Ranges {
-    type Output = Range<isize>;
+    type Output = Interval;
     fn index(&self, index: char) -> &Self::Output {
         match index {
             'x' => &self.x,
@@ -199,20 +199,19 @@ struct Part {
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse(s: &str) -> Input {
-        all_consuming(parse_inner)(s).unwrap_or_report().1
+    pub(super) fn parse(s: &str) -> Result<Input<'_>, SolveError> {
+        parse_all(s, parse_inner)
     }
 
-    fn parse_inner(s: &str) -> IResult<&str, Input> {
+    fn parse_inner(s: &str) -> IResult<&str, Input<'_>> {
         let (s, workflows) = many1(terminated(parse_workflow, line_ending))(s)?;
         let (s, _) = line_ending(s)?;
         let (s, parts) = many1(terminated(parse_part, line_ending))(s)?;
         Ok((s, Input { workflows, parts }))
     }
 
-    fn parse_workflow(s: &str) -> IResult<&str, Workflow> {
+    fn parse_workflow(s: &str) -> IResult<&str, Workflow<'_>> {
         let (s, name) = alpha1(s)?;
         let (s, _) = char('{')(s)?;
         let (s, rules) = separated_list1(char(','), parse_rule)(s)?;
@@ -241,7 +240,7 @@ mod parser {
         Ok((i, part))
     }
 
-    fn parse_rule(s: &str) -> IResult<&str, Rule> {
+    fn parse_rule(s: &str) -> IResult<&str, Rule<'_>> {
         let (s, category) = alt((char('a'), char('m'), char('x'), char('s')))(s)?;
         let (s, operator) = alt((char('<'), char('>')))(s)?;
         let (s, value) = parse_isize(s)?;
@@ -256,11 +255,11 @@ mod parser {
         Ok((s, rule))
     }
 
-    fn parse_destination(s: &str) -> IResult<&str, Destination> {
+    fn parse_destination(s: &str) -> IResult<&str, Destination<'_>> {
         let (s, dest) = alt((
             value(Destination::Accepted, tag("A")),
             value(Destination::Rejected, tag("R")),
-            map(take_while1(|c: char| c.is_lowercase()), |n| Destination::Workflow(n)),
+            map(take_while1(|c: char| c.is_lowercase()), Destination::Workflow),
         ))(s)?;
         Ok((s, dest))
     }
@@ -291,10 +290,10 @@ hdj{m>838:A,pv}
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(example()).to_string(), "19114");
+    assert_eq!(part_1(example()).unwrap().to_string(), "19114");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(example()).to_string(), "167409079868000");
+    assert_eq!(part_2(example()).unwrap().to_string(), "167409079868000");
 }
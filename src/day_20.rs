@@ -1,114 +1,168 @@
 //! https://adventofcode.com/2023/day/20
 
+use crate::SolveError;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let Input { mut modules, input_map: _ } = parser::parse(input);
-    let mut events = VecDeque::new();
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let Input { mut modules, input_map: _ } = parser::parse(input)?;
 
     let mut total = HashMap::<Pulse, usize>::new();
     for _ in 0..1000 {
-        events.push_back(Event {
-            from: None,
-            to: "broadcaster",
-            pulse: Pulse::Low,
+        press_button(&mut modules, |event| {
+            *total.entry(event.pulse).or_default() += 1;
         });
+    }
 
-        while let Some(event) = events.pop_front() {
-            let Event { from, to, pulse } = event;
-            *total.entry(pulse).or_default() += 1;
+    Ok(total.values().product::<usize>())
+}
 
-            let Some(m) = modules.get_mut(to) else {
-                continue;
-            };
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse(input)?;
 
-            let out: Option<Pulse> = match &mut m.r#type {
-                Type::Broadcaster => Some(pulse),
-                Type::FlipFlop { state, inputs: _ } => {
-                    if matches!(pulse, Pulse::Low) {
-                        state.toggle();
-                        let p = match state {
-                            OnOff::On => Pulse::High,
-                            OnOff::Off => Pulse::Low,
-                        };
-                        Some(p)
-                    } else {
-                        None
-                    }
-                }
-                Type::Conjunction { inputs } => {
-                    let from = from.unwrap();
-                    let entry = inputs.get_mut(from).unwrap();
-                    *entry = pulse;
-                    let p = if inputs.values().all(|p| matches!(p, Pulse::High)) {
-                        Pulse::Low
-                    } else {
-                        Pulse::High
+    // `rx` is fed by a single conjunction, which only sends a low pulse once all of its inputs have most recently sent it a high pulse.
+    // Each of those inputs is the output of an independent sub-counter, so find the period of each and combine them.
+    let cycles = feeder_cycles("rx", &input)?;
+    if let Some((name, _)) = cycles.iter().find(|(_name, cycle)| cycle.offset != cycle.cycle_len) {
+        return Err(SolveError::Invalid(format!(
+            "{name} does not send its first high pulse after exactly one cycle"
+        )));
+    }
+
+    cycles
+        .into_iter()
+        .map(|(_name, cycle)| cycle.cycle_len)
+        .reduce(|c1, c2| c1 * c2 / (gcd::Gcd::gcd(c1, c2)))
+        .ok_or_else(|| SolveError::Invalid("The conjunction feeding rx has no inputs".to_string()))
+}
+
+/// Presses the button once and processes pulses until the system settles.
+///
+/// Calls `on_event` for every pulse sent, including the initial pulse from the button.
+fn press_button<'a, F>(modules: &mut HashMap<ModuleName<'a>, Module<'a>>, mut on_event: F)
+where
+    F: FnMut(&Event<'a>),
+{
+    let mut events = VecDeque::new();
+    events.push_back(Event {
+        from: None,
+        to: "broadcaster",
+        pulse: Pulse::Low,
+    });
+
+    while let Some(event) = events.pop_front() {
+        on_event(&event);
+        let Event { from, to, pulse } = event;
+
+        let Some(m) = modules.get_mut(to) else {
+            continue;
+        };
+
+        let out: Option<Pulse> = match &mut m.r#type {
+            Type::Broadcaster => Some(pulse),
+            Type::FlipFlop { state, inputs: _ } => {
+                if matches!(pulse, Pulse::Low) {
+                    state.toggle();
+                    let p = match state {
+                        OnOff::On => Pulse::High,
+                        OnOff::Off => Pulse::Low,
                     };
                     Some(p)
+                } else {
+                    None
                 }
-            };
-            if let Some(p) = out {
-                for dst in m.destinations.iter() {
-                    events.push_back(Event {
-                        from: Some(&m.name),
-                        to: dst,
-                        pulse: p,
-                    });
-                }
+            }
+            Type::Conjunction { inputs } => {
+                let from = from.unwrap();
+                let entry = inputs.get_mut(from).unwrap();
+                *entry = pulse;
+                let p = if inputs.values().all(|p| matches!(p, Pulse::High)) {
+                    Pulse::Low
+                } else {
+                    Pulse::High
+                };
+                Some(p)
+            }
+        };
+        if let Some(p) = out {
+            for dst in m.destinations.iter() {
+                events.push_back(Event {
+                    from: Some(m.name),
+                    to: dst,
+                    pulse: p,
+                });
             }
         }
     }
-
-    total.values().product::<usize>()
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse(input);
+/// Upper bound on the button presses spent looking for feeder cycles.
+const MAX_PRESSES: usize = 100_000;
+
+/// Finds the cycle of each module feeding the conjunction that feeds `target`.
+///
+/// The cycle of a feeder is found by pressing the button until the feeder has sent a high pulse to the conjunction twice.
+/// Gives up after [`MAX_PRESSES`] presses.
+fn feeder_cycles<'a>(target: ModuleName<'a>, input: &Input<'a>) -> Result<Vec<(ModuleName<'a>, Cycle)>, SolveError> {
+    let conjunction = match input.input_map.get(target) {
+        Some(inputs) if inputs.len() == 1 => *inputs.iter().next().unwrap(),
+        _ => return Err(SolveError::Invalid(format!("{target} is not fed by a single module"))),
+    };
+    if !input
+        .modules
+        .get(conjunction)
+        .is_some_and(|m| matches!(m.r#type, Type::Conjunction { .. }))
+    {
+        return Err(SolveError::Invalid(format!("{target} is not fed by a conjunction")));
+    }
 
-    // let rx_inputs = &input.input_map["rx"];
-    // for i in rx_inputs {
-    // let cycle = cycle(i, &input);
-    // }
+    let mut feeders: Vec<ModuleName> = input.input_map.get(conjunction).into_iter().flatten().copied().collect();
+    feeders.sort();
 
-    ""
-}
+    // Button presses at which each feeder sent a high pulse to the conjunction.
+    let mut high_pulses: HashMap<ModuleName, Vec<usize>> = HashMap::new();
 
-/// Find cycle
-fn cycle<'a>(name: ModuleName<'a>, input: &Input<'a>) -> Cycle {
-    let m = &input.modules[name];
-    match &m.r#type {
-        Type::Broadcaster => Cycle { offset: 0, cycle_len: 1 },
-        Type::FlipFlop { inputs, state: _ } => {
-            for i in inputs {
-                dbg!(i, cycle(i, input));
-            }
-            Cycle {
-                offset: 3,    // todo
-                cycle_len: 3, // todo
-            }
+    let mut modules = input.modules.clone();
+    let mut presses = 0;
+    while !feeders.iter().all(|f| high_pulses.get(f).is_some_and(|p| p.len() >= 2)) {
+        if presses == MAX_PRESSES {
+            return Err(SolveError::Invalid(format!(
+                "No cycle found for the inputs of {conjunction} within {MAX_PRESSES} button presses"
+            )));
         }
-        Type::Conjunction { inputs } => {
-            for (i, p) in inputs {
-                dbg!(i, cycle(i, input));
-            }
-
-            Cycle {
-                offset: 4,    // todo
-                cycle_len: 4, // todo
+        presses += 1;
+        press_button(&mut modules, |event| {
+            if event.to == conjunction && matches!(event.pulse, Pulse::High) {
+                let p = high_pulses.entry(event.from.unwrap()).or_default();
+                if p.last() != Some(&presses) {
+                    p.push(presses);
+                }
             }
-        }
+        });
     }
+
+    Ok(feeders
+        .into_iter()
+        .map(|name| {
+            let p = &high_pulses[name];
+            let cycle = Cycle {
+                offset: p[0],
+                cycle_len: p[1] - p[0],
+            };
+            (name, cycle)
+        })
+        .collect())
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Cycle {
-    /// Offset from start of simulation until first low pulse.
+    /// Number of button presses until the first high pulse.
     offset: usize,
 
-    /// Cycle length.
+    /// Number of button presses between high pulses.
     cycle_len: usize,
 }
 
@@ -167,13 +221,9 @@ impl OnOff {
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse(s: &str) -> Input {
-        let lines = s
-            .lines()
-            .map(|line| all_consuming(parse_line)(line).expect_or_report(line).1)
-            .collect::<Vec<Line>>();
+    pub(super) fn parse(s: &str) -> Result<Input<'_>, SolveError> {
+        let lines = parse_lines(s, parse_line)?;
 
         let mut input_map: HashMap<ModuleName, HashSet<ModuleName>> = HashMap::new();
         for line in &lines {
@@ -191,14 +241,14 @@ mod parser {
                     r#type: match line.r#type {
                         Type::Broadcaster => super::Type::Broadcaster,
                         Type::FlipFlop => {
-                            let inputs = input_map.get(line.name).unwrap().clone();
+                            let inputs = input_map.get(line.name).cloned().unwrap_or_default();
                             super::Type::FlipFlop {
                                 inputs,
                                 state: OnOff::default(),
                             }
                         }
                         Type::Conjunction => {
-                            let inputs = HashMap::from_iter(input_map.get(&line.name).unwrap().iter().map(|&input| (input, Pulse::Low)));
+                            let inputs = HashMap::from_iter(input_map.get(&line.name).into_iter().flatten().map(|&input| (input, Pulse::Low)));
                             super::Type::Conjunction { inputs }
                         }
                     },
@@ -207,7 +257,7 @@ mod parser {
             );
         }
 
-        Input { modules, input_map }
+        Ok(Input { modules, input_map })
     }
 
     struct Line<'a> {
@@ -223,7 +273,7 @@ mod parser {
         Conjunction,
     }
 
-    fn parse_line(s: &str) -> IResult<&str, Line> {
+    fn parse_line(s: &str) -> IResult<&str, Line<'_>> {
         let (s, (r#type, name)) = alt((
             value((Type::Broadcaster, "broadcaster"), tag("broadcaster")),
             pair(value(Type::FlipFlop, char('%')), alpha1),
@@ -260,10 +310,48 @@ broadcaster -> a
 
 #[test]
 fn part_1_example_1() {
-    assert_eq!(part_1(example_1()).to_string(), "32000000");
+    assert_eq!(part_1(example_1()).unwrap().to_string(), "32000000");
 }
 
 #[test]
 fn part_1_example_2() {
-    assert_eq!(part_1(example_2()).to_string(), "11687500");
+    assert_eq!(part_1(example_2()).unwrap().to_string(), "11687500");
+}
+
+#[test]
+fn feeder_cycles_example_2() {
+    let input = parser::parse(example_2()).unwrap();
+    let cycles = feeder_cycles("output", &input).unwrap();
+    assert_eq!(
+        cycles,
+        vec![("a", Cycle { offset: 1, cycle_len: 2 }), ("b", Cycle { offset: 1, cycle_len: 4 }),]
+    );
+}
+
+#[test]
+fn modules_without_inputs() {
+    let input = "broadcaster -> a\n%a -> b\n%c -> b\n&b -> rx\n&d -> a\n";
+    assert!(part_1(input).is_ok());
+}
+
+#[test]
+fn feeder_cycles_invalid() {
+    let input = parser::parse(example_2()).unwrap();
+    assert!(matches!(feeder_cycles("rx", &input), Err(SolveError::Invalid(_))));
+    assert!(matches!(feeder_cycles("con", &input), Err(SolveError::Invalid(_))));
+    assert!(matches!(feeder_cycles("inv", &input), Err(SolveError::Invalid(_))));
+}
+
+#[test]
+fn part_2_offset_cycle() {
+    // `b` sends a high pulse on every fourth press, starting with the second.
+    let input = "broadcaster -> a\n%a -> b\n%b -> con\n&con -> rx\n";
+    assert!(matches!(part_2(input), Err(SolveError::Invalid(_))));
+}
+
+#[test]
+fn feeder_cycles_gives_up_on_silent_feeder() {
+    // `b` is only fed by itself, so it never sends a pulse to `con`.
+    let input = parser::parse("broadcaster -> a\n%a -> con\n%b -> b, con\n&con -> output\n").unwrap();
+    assert!(matches!(feeder_cycles("output", &input), Err(SolveError::Invalid(_))));
 }
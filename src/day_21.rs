@@ -1,67 +1,141 @@
 //! https://adventofcode.com/2023/day/21
 
 use crate::lib::grid;
+use crate::lib::grid::bit_grid::BitGrid;
+use crate::lib::grid::render::Render;
+use crate::lib::grid::wrapping::Wrapping;
 use crate::lib::grid::Dir;
+use crate::lib::grid::Grid as _;
+use crate::lib::grid::GridTile;
 use crate::lib::grid::Pos;
+use crate::SolveError;
+use colored::Color;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
     solve(input, 64)
 }
 
-pub fn solve(input: &str, steps: usize) -> impl std::fmt::Display {
-    let grid = parse_grid(input);
-    let (start_pos, _) = grid.iter().find(|(_pos, tile)| matches!(tile, Tile::StartingPosition)).unwrap();
+pub fn solve(input: &str, steps: usize) -> Result<usize, SolveError> {
+    let grid = parse_grid(input)?;
+    let start_pos = find_start(&grid)?;
+    let plots = BitGrid::from_grid(&grid, |tile| !matches!(tile, Tile::Rock));
 
-    let mut s1: HashSet<Pos> = HashSet::new();
-    let mut s2: HashSet<Pos> = HashSet::new();
-    s2.insert(start_pos);
+    let mut reachable = BitGrid::new(grid.n_rows, grid.n_cols);
+    reachable.insert(start_pos);
     for _ in 0..steps {
-        (s1, s2) = (s2, s1);
-        s2.clear();
-
-        // println!();
-        // grid.dbg(|pos, tile| match s1.contains(&pos) {
-        //     true => 'O',
-        //     false => match tile {
-        //         Tile::GardenPlot => '.',
-        //         Tile::Rock => '#',
-        //         Tile::StartingPosition => 'S',
-        //     },
-        // });
-
-        for pos in s1.iter() {
-            let reachable = Dir::every_direction()
-                .into_iter()
-                .map(|dir| pos.step(dir))
-                .filter(|p| match grid.get(*p) {
-                    Some(Tile::Rock) | None => false,
-                    Some(Tile::StartingPosition | Tile::GardenPlot) => true,
-                });
-            s2.extend(reachable);
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("\n{}", Render::tiles(&grid).overlay(reachable.iter(), 'O', Color::Green).colour(true));
+        }
+
+        let mut next = reachable.shift(Dir::N);
+        for dir in [Dir::W, Dir::S, Dir::E] {
+            next |= &reachable.shift(dir);
         }
+        next &= &plots;
+        reachable = next;
     }
 
-    s2.len()
+    Ok(reachable.count_ones())
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    solve_infinite_extrapolated(input, 26501365)
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    ""
+/// Returns the number of garden plots reachable in exactly `steps` steps when the map is tiled infinitely in every direction.
+///
+/// Brute force: walks every plot within reach.
+pub fn solve_infinite(input: &str, steps: usize) -> Result<usize, SolveError> {
+    let grid = parse_grid(input)?;
+    let start_pos = find_start(&grid)?;
+    let distances = distances_infinite(&grid, start_pos, steps);
+    Ok(count_reachable(&distances, steps))
 }
 
+/// Returns the number of garden plots reachable in exactly `steps` steps when the map is tiled infinitely in every direction.
+///
+/// Assumes a square map with the start in the middle and a clear path from the start to each edge,
+/// so that the number of reachable plots grows quadratically for every map width walked.
+/// Samples the first three points of that quadratic by brute force and extrapolates from there.
+fn solve_infinite_extrapolated(input: &str, steps: usize) -> Result<usize, SolveError> {
+    let grid = parse_grid(input)?;
+    let start_pos = find_start(&grid)?;
+    if grid.n_rows != grid.n_cols {
+        return Err(SolveError::Invalid(format!(
+            "The map is {}x{}, expected a square",
+            grid.n_rows, grid.n_cols
+        )));
+    }
+
+    let width = grid.n_rows;
+    let rem = steps % width;
+    if steps <= rem + 2 * width {
+        // Nothing to extrapolate
+        return solve_infinite(input, steps);
+    }
+
+    let distances = distances_infinite(&grid, start_pos, rem + 2 * width);
+    let [y0, y1, y2] = [rem, rem + width, rem + 2 * width].map(|s| count_reachable(&distances, s) as isize);
+
+    // Newton's forward difference formula
+    let x = (steps / width) as isize;
+    let y = y0 + x * (y1 - y0) + x * (x - 1) / 2 * (y2 - 2 * y1 + y0);
+    Ok(y as usize)
+}
+
+/// Returns the shortest distance to every garden plot within `max_steps` steps, on an infinitely tiled map.
+fn distances_infinite(grid: &grid::vec_of_vecs::Grid<Tile>, start_pos: Pos, max_steps: usize) -> Vec<usize> {
+    let grid = Wrapping::new(grid);
+
+    let mut distances = vec![];
+    let mut visited: HashSet<Pos> = HashSet::from([start_pos]);
+    let mut queue = VecDeque::from([(start_pos, 0)]);
+    while let Some((pos, dist)) = queue.pop_front() {
+        distances.push(dist);
+        if dist == max_steps {
+            continue;
+        }
+        for (p, tile) in grid.neighbours4(pos) {
+            if !matches!(tile, Tile::Rock) && visited.insert(p) {
+                queue.push_back((p, dist + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Returns the number of plots reachable in exactly `steps` steps, given the shortest distance to each plot.
+///
+/// A plot reached in `n` steps can be reached again in `n + 2` steps by stepping back and forth,
+/// so every plot at a shortest distance of matching parity is reachable.
+fn count_reachable(distances: &[usize], steps: usize) -> usize {
+    distances.iter().filter(|&&d| d <= steps && d % 2 == steps % 2).count()
+}
+
+fn find_start(grid: &grid::vec_of_vecs::Grid<Tile>) -> Result<Pos, SolveError> {
+    grid.iter()
+        .find(|(_pos, tile)| matches!(tile, Tile::StartingPosition))
+        .map(|(start_pos, _)| start_pos)
+        .ok_or_else(|| SolveError::Invalid("The map has no starting position".to_string()))
+}
+
+#[derive(GridTile)]
 enum Tile {
+    #[tile('.')]
     GardenPlot,
+    #[tile('#')]
     Rock,
+    #[tile('S')]
     StartingPosition,
 }
 
-fn parse_grid(input: &str) -> grid::vec_of_vecs::Grid<Tile> {
-    grid::vec_of_vecs::Grid::<Tile>::parse_char_grid(input, |c| match c {
-        '.' => Tile::GardenPlot,
-        '#' => Tile::Rock,
-        'S' => Tile::StartingPosition,
-        _ => panic!(),
-    })
+fn parse_grid(input: &str) -> Result<grid::vec_of_vecs::Grid<Tile>, grid::vec_of_vecs::ParseError> {
+    grid::vec_of_vecs::Grid::parse_tiles(input)
 }
 
 #[cfg(test)]
@@ -83,10 +157,31 @@ fn example() -> &'static str {
 
 #[test]
 fn part_1_example() {
-    assert_eq!(solve(example(), 6).to_string(), "16");
+    assert_eq!(solve(example(), 6).unwrap(), 16);
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(example()).to_string(), "");
+    assert_eq!(solve_infinite(example(), 6).unwrap(), 16);
+    assert_eq!(solve_infinite(example(), 10).unwrap(), 50);
+    assert_eq!(solve_infinite(example(), 50).unwrap(), 1594);
+    assert_eq!(solve_infinite(example(), 100).unwrap(), 6536);
+    assert_eq!(solve_infinite(example(), 500).unwrap(), 167004);
+}
+
+#[test]
+fn invalid_maps() {
+    assert!(matches!(solve("...\n.#.\n...\n", 6), Err(SolveError::Invalid(_))));
+    assert!(matches!(solve("..S\n.x.\n...\n", 6), Err(SolveError::Grid(_))));
+    assert!(matches!(solve_infinite_extrapolated("..S.\n....\n", 100), Err(SolveError::Invalid(_))));
+}
+
+#[test]
+fn part_2_extrapolation_real() {
+    let input = crate::inputs::cached_for_test(21);
+    let steps = 65 + 3 * 131;
+    assert_eq!(
+        solve_infinite_extrapolated(&input, steps).unwrap(),
+        solve_infinite(&input, steps).unwrap()
+    );
 }
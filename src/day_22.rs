@@ -0,0 +1,122 @@
+//! https://adventofcode.com/2023/day/22
+
+use crate::lib::space::Cuboid;
+use crate::lib::space::Pos3;
+use crate::SolveError;
+use std::collections::HashMap;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let supporters = settle(parser::parse(input)?);
+
+    // A brick can be disintegrated if it isn't the only brick supporting any other brick.
+    let n_bricks = supporters.len();
+    let mut load_bearing = vec![false; n_bricks];
+    for s in supporters.iter() {
+        if let [only] = s[..] {
+            load_bearing[only] = true;
+        }
+    }
+    Ok(load_bearing.into_iter().filter(|b| !b).count())
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let supporters = settle(parser::parse(input)?);
+
+    let n_bricks = supporters.len();
+    Ok((0..n_bricks)
+        .map(|disintegrated| {
+            // Bricks are settled from the ground up, so every supporter of a brick is settled before the brick itself.
+            let mut fallen = vec![false; n_bricks];
+            fallen[disintegrated] = true;
+            for brick in disintegrated + 1..n_bricks {
+                let s = &supporters[brick];
+                if !s.is_empty() && s.iter().all(|&idx| fallen[idx]) {
+                    fallen[brick] = true;
+                }
+            }
+            fallen.into_iter().filter(|&f| f).count() - 1
+        })
+        .sum::<usize>())
+}
+
+/// Lets every brick fall as far down as it can.
+///
+/// Returns the supporters of each brick, with bricks indexed in the order they settled (lowest first).
+/// Bricks resting on the ground have no supporters.
+fn settle(mut bricks: Vec<Cuboid>) -> Vec<Vec<usize>> {
+    bricks.sort_by_key(|brick| brick.min.z);
+
+    // Height of the highest settled brick at each (x, y), and the index of that brick.
+    let mut top: HashMap<(isize, isize), (isize, usize)> = HashMap::new();
+
+    let mut supporters = Vec::with_capacity(bricks.len());
+    for (idx, brick) in bricks.iter().enumerate() {
+        let footprint = || (brick.min.x..=brick.max.x).flat_map(|x| (brick.min.y..=brick.max.y).map(move |y| (x, y)));
+
+        let resting_z = footprint().filter_map(|xy| top.get(&xy)).map(|&(z, _)| z + 1).max().unwrap_or(1);
+
+        let mut s: Vec<usize> = footprint()
+            .filter_map(|xy| top.get(&xy))
+            .filter(|&&(z, _)| z + 1 == resting_z)
+            .map(|&(_, supporter)| supporter)
+            .collect();
+        s.sort_unstable();
+        s.dedup();
+        supporters.push(s);
+
+        let height = brick.max.z - brick.min.z;
+        for xy in footprint() {
+            top.insert(xy, (resting_z + height, idx));
+        }
+    }
+
+    supporters
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Cuboid>, SolveError> {
+        parse_lines(s, parse_line)
+    }
+
+    fn parse_line(s: &str) -> IResult<&str, Cuboid> {
+        let (s, (a, b)) = separated_pair(parse_pos3, char('~'), parse_pos3)(s)?;
+        Ok((s, Cuboid::new(a, b)))
+    }
+
+    fn parse_pos3(s: &str) -> IResult<&str, Pos3> {
+        let (s, x) = parse_isize(s)?;
+        let (s, _) = char(',')(s)?;
+        let (s, y) = parse_isize(s)?;
+        let (s, _) = char(',')(s)?;
+        let (s, z) = parse_isize(s)?;
+        Ok((s, Pos3 { x, y, z }))
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "5");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "7");
+}
@@ -0,0 +1,169 @@
+//! https://adventofcode.com/2023/day/23
+
+use crate::lib::grid;
+use crate::lib::grid::Dir;
+use crate::lib::grid::Grid as _;
+use crate::lib::grid::Pos;
+use crate::SolveError;
+use std::collections::HashMap;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    longest_hike(input, true)
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    longest_hike(input, false)
+}
+
+fn longest_hike(input: &str, slippery: bool) -> Result<usize, SolveError> {
+    let grid = parse_grid(input)?;
+    let start = Pos(0, 1);
+    let end = Pos(grid.n_rows as isize - 1, grid.n_cols as isize - 2);
+    if !matches!(grid.get(start), Some(Tile::Path)) {
+        return Err(SolveError::Invalid("The start is not a path in the top left".to_string()));
+    }
+    if !matches!(grid.get(end), Some(Tile::Path)) {
+        return Err(SolveError::Invalid("The end is not a path in the bottom right".to_string()));
+    }
+
+    let junctions = junctions(&grid, start, end);
+    let edges = edges(&grid, &junctions, slippery);
+
+    let mut visited = vec![false; junctions.len()];
+    visited[0] = true;
+    longest_path(&edges, &mut visited, 0, 1).ok_or_else(|| SolveError::Invalid("No path from start to end".to_string()))
+}
+
+/// Returns the start, the end and every tile where the path forks, indexed by node id (start is 0, end is 1).
+fn junctions(grid: &grid::vec_of_vecs::Grid<Tile>, start: Pos, end: Pos) -> Vec<Pos> {
+    let forks = grid.iter().filter(|(_pos, tile)| !matches!(tile, Tile::Forest)).filter(|(pos, _tile)| {
+        let open_neighbours = grid.neighbours4(*pos).filter(|(_pos, tile)| !matches!(tile, Tile::Forest)).count();
+        open_neighbours >= 3
+    });
+
+    [start, end].into_iter().chain(forks.map(|(pos, _tile)| pos)).collect()
+}
+
+/// Returns the edges from each junction, as pairs of destination junction and path length.
+///
+/// If `slippery` is set, slopes can only be walked down, and edges that would require walking up a slope are left out.
+fn edges(grid: &grid::vec_of_vecs::Grid<Tile>, junctions: &[Pos], slippery: bool) -> Vec<Vec<(usize, usize)>> {
+    let node_ids: HashMap<Pos, usize> = junctions.iter().enumerate().map(|(id, &pos)| (pos, id)).collect();
+
+    junctions
+        .iter()
+        .map(|&from| {
+            Dir::every_direction()
+                .into_iter()
+                .filter_map(|dir| {
+                    let mut pos = from;
+                    let mut dir = dir;
+                    let mut steps = 0;
+                    loop {
+                        pos = pos.step(dir);
+                        steps += 1;
+                        match grid.get(pos) {
+                            None | Some(Tile::Forest) => return None,
+                            Some(Tile::Slope(slope)) if slippery && *slope != dir => return None,
+                            _ => {}
+                        }
+                        if let Some(&id) = node_ids.get(&pos) {
+                            return Some((id, steps));
+                        }
+
+                        // Follow the path, there's only one way to go that doesn't turn back.
+                        dir = [dir, dir.turn_left(), dir.turn_right()]
+                            .into_iter()
+                            .find(|&d| matches!(grid.get(pos.step(d)), Some(t) if !matches!(t, Tile::Forest)))?;
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the length of the longest path from `node` to `end` that doesn't visit any node twice.
+fn longest_path(edges: &[Vec<(usize, usize)>], visited: &mut [bool], node: usize, end: usize) -> Option<usize> {
+    if node == end {
+        return Some(0);
+    }
+
+    let mut longest = None;
+    for &(to, steps) in edges[node].iter() {
+        if visited[to] {
+            continue;
+        }
+        visited[to] = true;
+        if let Some(len) = longest_path(edges, visited, to, end) {
+            longest = longest.max(Some(steps + len));
+        }
+        visited[to] = false;
+    }
+    longest
+}
+
+enum Tile {
+    Path,
+    Forest,
+    Slope(Dir),
+}
+
+fn parse_grid(input: &str) -> Result<grid::vec_of_vecs::Grid<Tile>, grid::vec_of_vecs::ParseError> {
+    grid::vec_of_vecs::Grid::<Tile>::try_parse_char_grid(input, |c| match c {
+        '.' => Some(Tile::Path),
+        '#' => Some(Tile::Forest),
+        '^' => Some(Tile::Slope(Dir::N)),
+        '<' => Some(Tile::Slope(Dir::W)),
+        'v' => Some(Tile::Slope(Dir::S)),
+        '>' => Some(Tile::Slope(Dir::E)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "94");
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "154");
+}
+
+#[test]
+fn invalid_maps() {
+    assert!(matches!(part_1("#\n#\n"), Err(SolveError::Invalid(_))));
+    assert!(matches!(part_1("##.#\n#..#\n#.##\n"), Err(SolveError::Invalid(_))));
+    assert!(matches!(part_1("#.##\n####\n##.#\n"), Err(SolveError::Invalid(_))));
+}
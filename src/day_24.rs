@@ -0,0 +1,195 @@
+//! https://adventofcode.com/2023/day/24
+
+use crate::lib::space::Pos3;
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    solve_part_1(input, 200000000000000.0, 400000000000000.0)
+}
+
+/// Returns the number of pairs of hailstones whose future paths cross within the test area, ignoring the Z axis.
+pub fn solve_part_1(input: &str, min: f64, max: f64) -> Result<usize, SolveError> {
+    let hailstones = parser::parse(input)?;
+
+    let mut count = 0;
+    for (idx, a) in hailstones.iter().enumerate() {
+        for b in &hailstones[idx + 1..] {
+            // Solve a.pos + t_a * a.vel = b.pos + t_b * b.vel in the XY plane.
+            let det = (b.vel.x * a.vel.y - a.vel.x * b.vel.y) as f64;
+            if det == 0.0 {
+                // Parallel
+                continue;
+            }
+            let dx = (b.pos.x - a.pos.x) as f64;
+            let dy = (b.pos.y - a.pos.y) as f64;
+            let t_a = (b.vel.x as f64 * dy - b.vel.y as f64 * dx) / det;
+            let t_b = (a.vel.x as f64 * dy - a.vel.y as f64 * dx) / det;
+            if t_a < 0.0 || t_b < 0.0 {
+                // Crossed in the past
+                continue;
+            }
+
+            let x = a.pos.x as f64 + t_a * a.vel.x as f64;
+            let y = a.pos.y as f64 + t_a * a.vel.y as f64;
+            if (min..=max).contains(&x) && (min..=max).contains(&y) {
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let hailstones = parser::parse(input)?;
+    let rock = throw(&hailstones)?;
+    Ok(rock.pos.x + rock.pos.y + rock.pos.z)
+}
+
+/// Returns the trajectory of a rock thrown so that it hits every hailstone.
+///
+/// In the reference frame of the rock, every hailstone passes through the rock's starting position.
+/// Searches XY velocities outwards from zero: for each candidate, intersects the paths of the first two hailstones in that reference frame,
+/// then checks that every other hailstone hits the same point, at a whole number of nanoseconds.
+fn throw(hailstones: &[Hailstone]) -> Result<Hailstone, SolveError> {
+    let [h0, h1, ..] = hailstones else {
+        return Err(SolveError::Invalid("Need at least two hailstones".to_string()));
+    };
+
+    const MAX_SPEED: isize = 1000;
+    for radius in 0..=MAX_SPEED {
+        let ring = (-radius..=radius).flat_map(|vx| (-radius..=radius).map(move |vy| (vx, vy)));
+        for (vx, vy) in ring.filter(|&(vx, vy)| vx.abs() == radius || vy.abs() == radius) {
+            // Hailstone velocities relative to the rock
+            let (d0x, d0y) = (h0.vel.x - vx, h0.vel.y - vy);
+            let (d1x, d1y) = (h1.vel.x - vx, h1.vel.y - vy);
+
+            let det = (d1x * d0y - d0x * d1y) as i128;
+            if det == 0 {
+                continue;
+            }
+            let dx = (h1.pos.x - h0.pos.x) as i128;
+            let dy = (h1.pos.y - h0.pos.y) as i128;
+            let t0 = d1x as i128 * dy - d1y as i128 * dx;
+            let t1 = d0x as i128 * dy - d0y as i128 * dx;
+            if t0 % det != 0 || t1 % det != 0 {
+                continue;
+            }
+            let (t0, t1) = (t0 / det, t1 / det);
+            if t0 < 0 || t1 < 0 || t0 == t1 {
+                continue;
+            }
+
+            // Z velocity follows from where the rock has to be at the two collision times.
+            let z0 = h0.pos.z as i128 + t0 * h0.vel.z as i128;
+            let z1 = h1.pos.z as i128 + t1 * h1.vel.z as i128;
+            if (z1 - z0) % (t1 - t0) != 0 {
+                continue;
+            }
+            let vz = (z1 - z0) / (t1 - t0);
+
+            let rock = Hailstone {
+                pos: Pos3 {
+                    x: (h0.pos.x as i128 + t0 * d0x as i128) as isize,
+                    y: (h0.pos.y as i128 + t0 * d0y as i128) as isize,
+                    z: (z0 - t0 * vz) as isize,
+                },
+                vel: Pos3 {
+                    x: vx,
+                    y: vy,
+                    z: vz as isize,
+                },
+            };
+            if hailstones.iter().all(|h| collides(&rock, h)) {
+                return Ok(rock);
+            }
+        }
+    }
+
+    Err(SolveError::Invalid("No rock trajectory hits every hailstone".to_string()))
+}
+
+/// Returns true if the rock and the hailstone are in the same place at some non-negative whole number of nanoseconds.
+fn collides(rock: &Hailstone, h: &Hailstone) -> bool {
+    // Along each axis, the gap closes at the difference in velocity.
+    let gap = h.pos - rock.pos;
+    let closing_speed = rock.vel - h.vel;
+    let axes = [(gap.x, closing_speed.x), (gap.y, closing_speed.y), (gap.z, closing_speed.z)];
+
+    let mut time = None;
+    for (gap, closing_speed) in axes {
+        if closing_speed == 0 {
+            if gap != 0 {
+                return false;
+            }
+            continue;
+        }
+        if gap % closing_speed != 0 {
+            return false;
+        }
+        let t = gap / closing_speed;
+        if t < 0 || time.is_some_and(|time| time != t) {
+            return false;
+        }
+        time = Some(t);
+    }
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hailstone {
+    pos: Pos3,
+    vel: Pos3,
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Hailstone>, SolveError> {
+        parse_lines(s, parse_line)
+    }
+
+    fn parse_line(s: &str) -> IResult<&str, Hailstone> {
+        let (s, (pos, vel)) = separated_pair(parse_pos3, delimited(space1, char('@'), space1), parse_pos3)(s)?;
+        Ok((s, Hailstone { pos, vel }))
+    }
+
+    fn parse_pos3(s: &str) -> IResult<&str, Pos3> {
+        let separator = || pair(char(','), space1);
+        let coordinate = || map(i64, |c| c as isize);
+        let (s, x) = coordinate()(s)?;
+        let (s, _) = separator()(s)?;
+        let (s, y) = coordinate()(s)?;
+        let (s, _) = separator()(s)?;
+        let (s, z) = coordinate()(s)?;
+        Ok((s, Pos3 { x, y, z }))
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(solve_part_1(example(), 7.0, 27.0).unwrap(), 2);
+}
+
+#[test]
+fn part_2_example() {
+    assert_eq!(part_2(example()).unwrap().to_string(), "47");
+}
+
+#[test]
+fn part_2_invalid() {
+    assert!(matches!(part_2("1, 2, 3 @ 1, 1, 1\n"), Err(SolveError::Invalid(_))));
+}
@@ -0,0 +1,154 @@
+//! https://adventofcode.com/2023/day/25
+
+use crate::SolveError;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+crate::solution!(part_1);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let graph = Graph::new(parser::parse(input)?);
+
+    // Every component is on one side of the cut or the other, so some sink is on the opposite side from the source.
+    let source = 0;
+    let group_size = (1..graph.n_nodes())
+        .find_map(|sink| graph.min_cut(source, sink, 3))
+        .ok_or_else(|| SolveError::Invalid("No cut of three wires found".to_string()))?;
+
+    Ok(group_size * (graph.n_nodes() - group_size))
+}
+
+/// Undirected graph of components, connected by wires.
+struct Graph {
+    /// Adjacency lists: neighbours and the index of the wire to each.
+    adjacent: Vec<Vec<(usize, usize)>>,
+    n_wires: usize,
+}
+impl Graph {
+    fn new(lines: Vec<Line>) -> Graph {
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        let mut adjacent: Vec<Vec<(usize, usize)>> = vec![];
+        let mut n_wires = 0;
+
+        let mut id = |name| {
+            *ids.entry(name).or_insert_with(|| {
+                adjacent.push(vec![]);
+                adjacent.len() - 1
+            })
+        };
+        let mut wires = vec![];
+        for line in lines {
+            let a = id(line.component);
+            for other in line.connected {
+                wires.push((a, id(other)));
+            }
+        }
+        for (a, b) in wires {
+            adjacent[a].push((b, n_wires));
+            adjacent[b].push((a, n_wires));
+            n_wires += 1;
+        }
+
+        Graph { adjacent, n_wires }
+    }
+
+    fn n_nodes(&self) -> usize {
+        self.adjacent.len()
+    }
+
+    /// If exactly `cut` wires separate `source` from `sink`, returns the size of the group connected to `source` once they are cut.
+    ///
+    /// Runs Edmonds-Karp with unit capacities, giving up as soon as the flow exceeds `cut`.
+    fn min_cut(&self, source: usize, sink: usize, cut: usize) -> Option<usize> {
+        // Flow through each wire, positive in the direction from the lower to the higher node id.
+        let mut flow = vec![0i8; self.n_wires];
+        let residual = |flow: &[i8], from: usize, to: usize, wire: usize| {
+            let f = if from < to { flow[wire] } else { -flow[wire] };
+            1 - f
+        };
+
+        let mut paths = 0;
+        loop {
+            // Breadth first search for an augmenting path in the residual graph.
+            let mut came_from: Vec<Option<(usize, usize)>> = vec![None; self.n_nodes()];
+            let mut reached = vec![false; self.n_nodes()];
+            reached[source] = true;
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                for &(next, wire) in &self.adjacent[node] {
+                    if !reached[next] && residual(&flow, node, next, wire) > 0 {
+                        reached[next] = true;
+                        came_from[next] = Some((node, wire));
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            if !reached[sink] {
+                // No augmenting path left, so the reached nodes are the source's side of a minimum cut.
+                return (paths == cut).then(|| reached.iter().filter(|&&r| r).count());
+            }
+
+            paths += 1;
+            if paths > cut {
+                return None;
+            }
+
+            let mut node = sink;
+            while let Some((prev, wire)) = came_from[node] {
+                flow[wire] += if prev < node { 1 } else { -1 };
+                node = prev;
+            }
+        }
+    }
+}
+
+struct Line<'a> {
+    component: &'a str,
+    connected: Vec<&'a str>,
+}
+
+mod parser {
+    use super::*;
+    use crate::my_nom_prelude::*;
+
+    pub(super) fn parse(s: &str) -> Result<Vec<Line<'_>>, SolveError> {
+        parse_lines(s, parse_line)
+    }
+
+    fn parse_line(s: &str) -> IResult<&str, Line<'_>> {
+        let (s, component) = alpha1(s)?;
+        let (s, _) = tag(": ")(s)?;
+        let (s, connected) = separated_list1(space1, alpha1)(s)?;
+        Ok((s, Line { component, connected }))
+    }
+}
+
+#[cfg(test)]
+fn example() -> &'static str {
+    &r#"
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr
+"#[1..] // Skip the first line ending
+}
+
+#[test]
+fn part_1_example() {
+    assert_eq!(part_1(example()).unwrap().to_string(), "54");
+}
+
+#[test]
+fn no_cut() {
+    assert!(matches!(part_1("abc: def\n"), Err(SolveError::Invalid(_))));
+}
@@ -0,0 +1,276 @@
+//! Puzzle inputs.
+//!
+//! Inputs are read from the cache directory (`input/`). A missing input is downloaded once from the
+//! Advent of Code website, which requires the session cookie of a logged in user, and then cached.
+//!
+//! Alternatively, a single input can be given up front, see [`Inputs::with_input`].
+
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Base URL of this year's puzzles.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2023";
+
+/// Environment variable holding the session cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable overriding [`DEFAULT_BASE_URL`].
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+#[derive(Debug, derive_more::Display)]
+pub enum Error {
+    #[display(fmt = "Failed to read {}: {}", "path.display()", source)]
+    Read { path: PathBuf, source: std::io::Error },
+
+    #[display(fmt = "Failed to write {}: {}", "path.display()", source)]
+    Write { path: PathBuf, source: std::io::Error },
+
+    #[display(
+        fmt = "Input for day {} is missing, and there is no session token to download it with (set {} or write it to {})",
+        day,
+        SESSION_VAR,
+        "session_file.display()"
+    )]
+    NoSession { day: usize, session_file: PathBuf },
+
+    #[display(fmt = "Failed to download {}: {}", url, source)]
+    Download { url: String, source: Box<ureq::Error> },
+
+    #[display(fmt = "Failed to read response from {}: {}", url, source)]
+    Response { url: String, source: std::io::Error },
+}
+impl std::error::Error for Error {}
+
+pub struct Inputs {
+    cache_dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+
+    /// Where the session token was looked for, for reporting when it's missing.
+    session_file: PathBuf,
+
+    /// Input to use instead of the cached inputs.
+    input: Option<String>,
+}
+impl Inputs {
+    /// Inputs cached under `input/`.
+    ///
+    /// The session token is taken from `$AOC_SESSION`, or else from `session` in the config directory
+    /// (`$XDG_CONFIG_HOME/advent-of-code`, falling back to `~/.config/advent-of-code`).
+    /// The base URL can be overridden with `$AOC_BASE_URL`.
+    pub fn from_env() -> Self {
+        let session = std::env::var(SESSION_VAR)
+            .ok()
+            .or_else(|| std::fs::read_to_string(config_dir().join("session")).ok())
+            .map(|session| session.trim().to_owned())
+            .filter(|session| !session.is_empty());
+        let base_url = std::env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_owned());
+        Self::new("input", base_url, session)
+    }
+
+    pub fn new(cache_dir: impl Into<PathBuf>, base_url: impl Into<String>, session: Option<String>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            base_url: base_url.into(),
+            session,
+            session_file: config_dir().join("session"),
+            input: None,
+        }
+    }
+
+    /// Uses the given input for any day, instead of the cached inputs.
+    pub fn with_input(self, input: String) -> Self {
+        Self { input: Some(input), ..self }
+    }
+
+    /// Returns the input for the given day, downloading it first if it isn't cached.
+    pub fn get(&self, day: usize) -> Result<String, Error> {
+        if let Some(input) = self.cached(day)? {
+            return Ok(input);
+        }
+
+        let input = self.download(day)?;
+        write(&self.path(day), &input)?;
+        Ok(input)
+    }
+
+    /// Returns the input for the given day if it is cached, without downloading it.
+    pub fn cached(&self, day: usize) -> Result<Option<String>, Error> {
+        if let Some(input) = &self.input {
+            return Ok(Some(input.clone()));
+        }
+
+        let path = self.path(day);
+        match std::fs::read_to_string(&path) {
+            Ok(input) => Ok(Some(input)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Read { path, source }),
+        }
+    }
+
+    /// Path of the cached input for the given day.
+    pub fn path(&self, day: usize) -> PathBuf {
+        self.cache_dir.join(format!("day_{day:02}.txt"))
+    }
+
+    fn download(&self, day: usize) -> Result<String, Error> {
+        let Some(session) = &self.session else {
+            return Err(Error::NoSession {
+                day,
+                session_file: self.session_file.clone(),
+            });
+        };
+
+        let url = format!("{}/day/{day}/input", self.base_url.trim_end_matches('/'));
+        log::info!("Downloading {url}");
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .set("User-Agent", "github.com/plul/advent-of-code-2023")
+            .call()
+            .map_err(|source| Error::Download {
+                url: url.clone(),
+                source: Box::new(source),
+            })?;
+        response.into_string().map_err(|source| Error::Response { url, source })
+    }
+}
+
+/// Reads the input at the given path, or from stdin if the path is `-`.
+pub fn read(path: &Path) -> Result<String, Error> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut input).map_err(|source| Error::Read {
+            path: PathBuf::from("stdin"),
+            source,
+        })?;
+        return Ok(input);
+    }
+
+    std::fs::read_to_string(path).map_err(|source| Error::Read {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn write(path: &Path, input: &str) -> Result<(), Error> {
+    let write = || {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, input)
+    };
+    write().map_err(|source| Error::Write {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Returns the cached input for the given day, for tests against a real input.
+///
+/// Never downloads, and panics if the input isn't there, so the test fails instead of passing without checking anything.
+#[cfg(test)]
+pub fn cached_for_test(day: usize) -> String {
+    Inputs::from_env()
+        .cached(day)
+        .unwrap()
+        .unwrap_or_else(|| panic!("No cached input for day {day}"))
+}
+
+fn config_dir() -> PathBuf {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .unwrap_or_default();
+    config_home.join("advent-of-code")
+}
+
+/// Serves a single HTTP request with the given status and body, and returns the base URL to reach it at.
+///
+/// The request line and headers are sent back over the returned channel.
+#[cfg(test)]
+fn mock_server(status: &str, body: &'static str) -> (String, std::sync::mpsc::Receiver<String>) {
+    use std::io::BufRead;
+    use std::io::Write;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+    let status = status.to_owned();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+            request.push_str(&line);
+        }
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        tx.send(request).unwrap();
+    });
+    (base_url, rx)
+}
+
+#[cfg(test)]
+fn temp_cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("advent-of-code-2023-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn downloads_missing_input_once() {
+    let cache_dir = temp_cache_dir("download");
+    let (base_url, requests) = mock_server("200 OK", "1abc2\npqr3stu8vwx\n");
+    let inputs = Inputs::new(&cache_dir, base_url, Some("cookie".to_owned()));
+
+    assert_eq!(inputs.get(1).unwrap(), "1abc2\npqr3stu8vwx\n");
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("GET /2023/day/1/input HTTP/1.1\r\n"), "{request}");
+    assert!(
+        request.lines().any(|header| header.eq_ignore_ascii_case("cookie: session=cookie")),
+        "{request}"
+    );
+
+    // The mock server is gone, so this can only come from the cache.
+    assert_eq!(std::fs::read_to_string(inputs.path(1)).unwrap(), "1abc2\npqr3stu8vwx\n");
+    assert_eq!(inputs.get(1).unwrap(), "1abc2\npqr3stu8vwx\n");
+
+    std::fs::remove_dir_all(cache_dir).unwrap();
+}
+
+#[test]
+fn failed_download_is_not_cached() {
+    let cache_dir = temp_cache_dir("failed");
+    let (base_url, _requests) = mock_server("400 Bad Request", "Please log in to get your puzzle input.");
+    let inputs = Inputs::new(&cache_dir, base_url, Some("expired".to_owned()));
+
+    assert!(matches!(inputs.get(2), Err(Error::Download { .. })));
+    assert!(!inputs.path(2).exists());
+}
+
+#[test]
+fn missing_input_without_session() {
+    let cache_dir = temp_cache_dir("no-session");
+    let inputs = Inputs::new(&cache_dir, "http://127.0.0.1:1", None);
+
+    assert!(matches!(inputs.cached(3), Ok(None)));
+    assert!(matches!(inputs.get(3), Err(Error::NoSession { day: 3, .. })));
+}
+
+#[test]
+fn given_input_overrides_cache() {
+    let cache_dir = temp_cache_dir("given");
+    let inputs = Inputs::new(&cache_dir, "http://127.0.0.1:1", None).with_input("Time: 7\n".to_owned());
+
+    assert_eq!(inputs.get(6).unwrap(), "Time: 7\n");
+    assert!(!inputs.path(6).exists());
+}
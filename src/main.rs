@@ -1,21 +1,32 @@
+use answers::Answers;
+use answers::Verdict;
 use clap::Parser;
+use clap::Subcommand;
 use colored::Colorize;
+use inputs::Inputs;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 use std::time::Instant;
 
+mod answers;
+mod bench;
+mod inputs;
+
 mod day_01;
 mod day_02;
 mod day_03;
 mod day_04;
 mod day_05;
-// mod day_06; // Solved by hand
-// mod day_07;
+mod day_06;
+mod day_07;
 mod day_08;
-// mod day_09;
+mod day_09;
 mod day_10;
-// mod day_11;
+mod day_11;
 mod day_12;
-// mod day_13;
+mod day_13;
 mod day_14;
 mod day_15;
 mod day_16;
@@ -24,121 +35,411 @@ mod day_18;
 mod day_19;
 mod day_20;
 mod day_21;
-// mod day_22;
-// mod day_23;
-// mod day_24;
-// mod day_25;
+mod day_22;
+mod day_23;
+mod day_24;
+mod day_25;
+
+/// Every day's solution, in calendar order.
+const SOLUTIONS: &[(usize, Solution)] = &[
+    (1, day_01::SOLUTION),
+    (2, day_02::SOLUTION),
+    (3, day_03::SOLUTION),
+    (4, day_04::SOLUTION),
+    (5, day_05::SOLUTION),
+    (6, day_06::SOLUTION),
+    (7, day_07::SOLUTION),
+    (8, day_08::SOLUTION),
+    (9, day_09::SOLUTION),
+    (10, day_10::SOLUTION),
+    (11, day_11::SOLUTION),
+    (12, day_12::SOLUTION),
+    (13, day_13::SOLUTION),
+    (14, day_14::SOLUTION),
+    (15, day_15::SOLUTION),
+    (16, day_16::SOLUTION),
+    (17, day_17::SOLUTION),
+    (18, day_18::SOLUTION),
+    (19, day_19::SOLUTION),
+    (20, day_20::SOLUTION),
+    (21, day_21::SOLUTION),
+    (22, day_22::SOLUTION),
+    (23, day_23::SOLUTION),
+    (24, day_24::SOLUTION),
+    (25, day_25::SOLUTION),
+];
+
+/// Solution to a day's puzzle.
+///
+/// Each `day_XX` module registers its own with [`solution!`].
+pub struct Solution {
+    /// Part functions, in order, with the answer rendered to a string.
+    pub parts: &'static [PartFn],
+
+    /// Writes a picture of the puzzle, for days with inputs too big to look at in the terminal.
+    pub export: Option<ExportFn>,
+}
+
+/// Solves one part of a puzzle for the given input.
+pub type PartFn = fn(&str) -> Result<String, SolveError>;
+
+/// Writes a picture of the puzzle for the given input to the given path.
+pub type ExportFn = fn(&str, &Path) -> Result<(), ExportError>;
+
+/// Registers the given part functions (and export function, if any) as the `SOLUTION` of the calling `day_XX` module.
+#[macro_export]
+macro_rules! solution {
+    ($($part:ident),+ $(,)?) => {
+        $crate::solution!(@solution [$($part),+] None);
+    };
+    ($($part:ident),+; export = $export:ident) => {
+        $crate::solution!(@solution [$($part),+] Some($export));
+    };
+    (@solution [$($part:ident),+] $export:expr) => {
+        pub const SOLUTION: $crate::Solution = $crate::Solution {
+            parts: &[$(|input: &str| $part(input).map(|answer| answer.to_string())),+],
+            export: $export,
+        };
+    };
+}
+
+/// Error from solving a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+pub enum SolveError {
+    /// The input could not be parsed.
+    ///
+    /// Line and column are 1-indexed.
+    #[display(fmt = "Failed to parse input at line {}, column {} ({:?})", line, column, kind)]
+    Parse {
+        line: usize,
+        column: usize,
+        kind: nom::error::ErrorKind,
+    },
+
+    /// The input could not be parsed as a grid.
+    #[display(fmt = "Failed to parse input: {}", _0)]
+    Grid(lib::grid::vec_of_vecs::ParseError),
+
+    /// The input was parsed, but does not hold up to what the puzzle promises.
+    #[display(fmt = "Invalid input: {}", _0)]
+    Invalid(String),
+}
+impl std::error::Error for SolveError {}
+impl From<lib::grid::vec_of_vecs::ParseError> for SolveError {
+    fn from(err: lib::grid::vec_of_vecs::ParseError) -> Self {
+        Self::Grid(err)
+    }
+}
+
+/// Error from exporting a picture of a puzzle.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum ExportError {
+    #[display(fmt = "{}", _0)]
+    Solve(SolveError),
+
+    #[display(fmt = "Failed to write the picture: {}", _0)]
+    Io(std::io::Error),
+}
+impl std::error::Error for ExportError {}
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     day: Option<usize>,
     part: Option<usize>,
+
+    /// Read the input from this file (or stdin if `-`) instead of `input/`
+    #[arg(long, value_name = "PATH", requires = "day")]
+    input: Option<PathBuf>,
+
+    /// List the days and parts that have a solution
+    #[arg(long)]
+    list: bool,
+
+    /// Check the answers against the known answers in `answers.toml`
+    #[arg(long, conflicts_with = "input")]
+    check: bool,
+
+    /// Write a picture of the day's puzzle to this path (PNG if it ends with `.png`, SVG for day 18, PPM otherwise)
+    #[arg(long, value_name = "PATH", requires = "day", conflicts_with_all = ["part", "check"])]
+    export: Option<PathBuf>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Time parsing and solving over many runs
+    Bench {
+        day: Option<usize>,
+        part: Option<usize>,
+
+        /// Read the input from this file (or stdin if `-`) instead of `input/`
+        #[arg(long, value_name = "PATH", requires = "day")]
+        input: Option<PathBuf>,
+
+        /// Number of timed runs of each part
+        #[arg(long, default_value_t = 100, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        iterations: usize,
+
+        /// Number of untimed runs of each part before the timed runs
+        #[arg(long, default_value_t = 10)]
+        warmup: usize,
+
+        /// Output the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, derive_more::Display)]
+enum Error {
+    #[display(fmt = "Day {} has no solution", _0)]
+    UnknownDay(usize),
+
+    #[display(fmt = "Day {} has no part {}", day, part)]
+    UnknownPart { day: usize, part: usize },
+
+    #[display(fmt = "{}", _0)]
+    Input(inputs::Error),
+
+    #[display(fmt = "{}", _0)]
+    Answers(answers::Error),
+
+    #[display(fmt = "Day {} Part {}: {}", day, part, source)]
+    Solve { day: usize, part: usize, source: SolveError },
+
+    #[display(fmt = "Day {} has nothing to export", _0)]
+    NoExport(usize),
+
+    #[display(fmt = "Day {}: {}", day, source)]
+    Export { day: usize, source: ExportError },
+}
+impl std::error::Error for Error {}
+impl From<inputs::Error> for Error {
+    fn from(err: inputs::Error) -> Self {
+        Error::Input(err)
+    }
+}
+
+fn main() -> ExitCode {
     env_logger::builder().parse_default_env().init();
     let cli = Cli::parse();
 
-    if let Some(day) = cli.day {
-        if let Some(part) = cli.part {
-            solve(day, part)?;
-        } else {
-            solve(day, 1)?;
-            solve(day, 2)?;
+    if cli.list {
+        for (day, solution) in SOLUTIONS {
+            let parts = (1..=solution.parts.len()).map(|part| part.to_string()).collect::<Vec<_>>();
+            println!("Day {day:>2}: part {}", parts.join(", "));
         }
-    } else {
-        for day in 1..=25 {
-            for part in 1..=2 {
-                solve(day, part)?;
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(Command::Bench {
+        day,
+        part,
+        input,
+        iterations,
+        warmup,
+        json,
+    }) = cli.command
+    {
+        let (runs, inputs) = match runs(day, part).and_then(|runs| Ok((runs, inputs(input.as_deref())?))) {
+            Ok(setup) => setup,
+            Err(err) => {
+                eprintln!("{}", err.to_string().red());
+                return ExitCode::FAILURE;
             }
+        };
+        return bench(&inputs, &runs, bench::Options { iterations, warmup }, json);
+    }
+
+    let (runs, inputs) = match runs(cli.day, cli.part).and_then(|runs| Ok((runs, inputs(cli.input.as_deref())?))) {
+        Ok(setup) => setup,
+        Err(err) => {
+            eprintln!("{}", err.to_string().red());
+            return ExitCode::FAILURE;
         }
+    };
+
+    if let (Some(day), Some(path)) = (cli.day, &cli.export) {
+        return match export(&inputs, day, path) {
+            Ok(()) => {
+                println!("Wrote day {day} to {}", path.display());
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("{}", err.to_string().red());
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if cli.check {
+        return match Answers::load(answers::DEFAULT_PATH) {
+            Ok(answers) => check(&inputs, &answers, &runs),
+            Err(err) => {
+                eprintln!("{}", Error::Answers(err).to_string().red());
+                ExitCode::FAILURE
+            }
+        };
     }
 
-    Ok(())
+    // A failing day doesn't stop the rest of the run.
+    let mut exit_code = ExitCode::SUCCESS;
+    for &(day, part) in &runs {
+        match solve(&inputs, day, part) {
+            Ok(Answer { answer, elapsed }) => {
+                let micros = elapsed.as_micros();
+                let time = match micros {
+                    x if x < 10_000 => format!("{}μs", micros).green(),
+                    x if x < 10_000_000 => format!("{}ms", micros / 1000).yellow(),
+                    _ => format!("{}s", micros / 1000 / 1000).red(),
+                };
+                println!("{time:>10}    Day {day} Part {part}: {answer}");
+            }
+            Err(err) => {
+                eprintln!("{}", err.to_string().red());
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+    }
+    exit_code
 }
 
-fn solve(day: usize, part: usize) -> Result<(), std::io::Error> {
-    let input = read_input(format!("day_{day:02}.txt"));
+/// Benchmarks every solution, printing a row (or with `json`, a JSON array of reports) as it goes.
+fn bench(inputs: &Inputs, runs: &[(usize, usize)], options: bench::Options, json: bool) -> ExitCode {
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut reports = Vec::new();
+    if !json {
+        bench::print_header();
+    }
+    for &(day, part) in runs {
+        match bench::bench(inputs, day, part, options) {
+            Ok(report) if json => reports.push(report),
+            Ok(report) => bench::print_row(&report),
+            Err(err) => {
+                eprintln!("{}", err.to_string().red());
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports).expect("Reports serialize to JSON"));
+    }
+    exit_code
+}
 
-    let now = Instant::now();
-    let solution = match (day, part) {
-        (1, 1) => day_01::part_1(&input?).to_string(),
-        (1, 2) => day_01::part_2(&input?).to_string(),
-        (2, 1) => day_02::part_1(&input?).to_string(),
-        (2, 2) => day_02::part_2(&input?).to_string(),
-        (3, 1) => day_03::part_1(&input?).to_string(),
-        (3, 2) => day_03::part_2(&input?).to_string(),
-        (4, 1) => day_04::part_1(&input?).to_string(),
-        (4, 2) => day_04::part_2(&input?).to_string(),
-        (5, 1) => day_05::part_1(&input?).to_string(),
-        (5, 2) => day_05::part_2(&input?).to_string(),
-        // (6, 1) => day_06::part_1(&input?).to_string(),
-        // (6, 2) => day_06::part_2(&input?).to_string(),
-        // (7, 1) => day_07::part_1(&input?).to_string(),
-        // (7, 2) => day_07::part_2(&input?).to_string(),
-        (8, 1) => day_08::part_1(&input?).to_string(),
-        (8, 2) => day_08::part_2(&input?).to_string(),
-        // (9, 1) => day_09::part_1(&input?).to_string(),
-        // (9, 2) => day_09::part_2(&input?).to_string(),
-        (10, 1) => day_10::part_1(&input?).to_string(),
-        (10, 2) => day_10::part_2(&input?).to_string(),
-        // (11, 1) => day_11::part_1(&input?).to_string(),
-        // (11, 2) => day_11::part_2(&input?).to_string(),
-        (12, 1) => day_12::part_1(&input?).to_string(),
-        (12, 2) => day_12::part_2(&input?).to_string(),
-        // (13, 1) => day_13::part_1(&input?).to_string(),
-        // (13, 2) => day_13::part_2(&input?).to_string(),
-        (14, 1) => day_14::part_1(&input?).to_string(),
-        (14, 2) => day_14::part_2(&input?).to_string(),
-        (15, 1) => day_15::part_1(&input?).to_string(),
-        (15, 2) => day_15::part_2(&input?).to_string(),
-        (16, 1) => day_16::part_1(&input?).to_string(),
-        (16, 2) => day_16::part_2(&input?).to_string(),
-        (17, 1) => day_17::part_1(&input?).to_string(),
-        (17, 2) => day_17::part_2(&input?).to_string(),
-        (18, 1) => day_18::part_1(&input?).to_string(),
-        (18, 2) => day_18::part_2(&input?).to_string(),
-        (19, 1) => day_19::part_1(&input?).to_string(),
-        (19, 2) => day_19::part_2(&input?).to_string(),
-        (20, 1) => day_20::part_1(&input?).to_string(),
-        (20, 2) => day_20::part_2(&input?).to_string(),
-        (21, 1) => day_21::part_1(&input?).to_string(),
-        (21, 2) => day_21::part_2(&input?).to_string(),
-        // (22, 1) => day_21::part_1(&input?).to_string(),
-        // (22, 2) => day_21::part_2(&input?).to_string(),
-        // (23, 1) => day_21::part_1(&input?).to_string(),
-        // (23, 2) => day_21::part_2(&input?).to_string(),
-        // (24, 1) => day_21::part_1(&input?).to_string(),
-        // (24, 2) => day_21::part_2(&input?).to_string(),
-        // (25, 1) => day_21::part_1(&input?).to_string(),
-        // (25, 2) => day_21::part_2(&input?).to_string(),
-        _ => return Ok(()),
-    };
-    let elapsed = now.elapsed();
+/// Runs every solution and marks its answer as pass, fail or unknown.
+///
+/// Fails if any answer differs from the known answer, or if any solution fails to run.
+fn check(inputs: &Inputs, answers: &Answers, runs: &[(usize, usize)]) -> ExitCode {
+    let (mut passed, mut failed, mut unknown, mut skipped) = (0, 0, 0, 0);
+    for &(day, part) in runs {
+        match solve(inputs, day, part) {
+            Ok(Answer { answer, .. }) => match answers.check(day, part, &answer) {
+                Verdict::Pass => {
+                    passed += 1;
+                    println!("{}    Day {day} Part {part}: {answer}", "   pass".green());
+                }
+                Verdict::Fail { expected } => {
+                    failed += 1;
+                    println!("{}    Day {day} Part {part}: {answer} (expected {expected})", "   FAIL".red());
+                }
+                Verdict::Unknown => {
+                    unknown += 1;
+                    println!("{}    Day {day} Part {part}: {answer}", "unknown".yellow());
+                }
+            },
+            Err(Error::Input(err @ inputs::Error::NoSession { .. })) => {
+                skipped += 1;
+                println!("{}    Day {day} Part {part}: {err}", "   skip".yellow());
+            }
+            Err(Error::Solve { source, .. }) => {
+                failed += 1;
+                println!("{}    Day {day} Part {part}: {source}", "  ERROR".red());
+            }
+            Err(err) => {
+                failed += 1;
+                println!("{}    Day {day} Part {part}: {err}", "  ERROR".red());
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {unknown} unknown, {skipped} skipped");
+    if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+/// Returns the inputs to run on, which is just the given input if there is one.
+fn inputs(input: Option<&Path>) -> Result<Inputs, Error> {
+    let inputs = Inputs::from_env();
+    match input {
+        Some(path) => Ok(inputs.with_input(inputs::read(path)?)),
+        None => Ok(inputs),
+    }
+}
 
-    let micros = elapsed.as_micros();
-    let time = match micros {
-        x if x < 10_000 => format!("{}μs", micros).green(),
-        x if x < 10_000_000 => format!("{}ms", micros / 1000).yellow(),
-        _ => format!("{}s", micros / 1000 / 1000).red(),
+/// Returns the days and parts to run: a single part, every part of a day, or every part of every day.
+fn runs(day: Option<usize>, part: Option<usize>) -> Result<Vec<(usize, usize)>, Error> {
+    let runs = match (day, part) {
+        (Some(day), Some(part)) => vec![(day, part)],
+        (Some(day), None) => (1..=find_solution(day)?.parts.len()).map(|part| (day, part)).collect(),
+        (None, _) => SOLUTIONS
+            .iter()
+            .flat_map(|(day, solution)| (1..=solution.parts.len()).map(|part| (*day, part)))
+            .collect(),
     };
+    Ok(runs)
+}
+
+fn find_solution(day: usize) -> Result<&'static Solution, Error> {
+    SOLUTIONS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, solution)| solution)
+        .ok_or(Error::UnknownDay(day))
+}
 
-    println!("{time:>10}    Day {day} Part {part}: {solution}");
+/// Answer to one part of a puzzle, and how long it took to solve.
+struct Answer {
+    answer: String,
+    elapsed: Duration,
+}
+
+fn find_part(day: usize, part: usize) -> Result<PartFn, Error> {
+    let solution = find_solution(day)?;
+    part.checked_sub(1)
+        .and_then(|idx| solution.parts.get(idx))
+        .copied()
+        .ok_or(Error::UnknownPart { day, part })
+}
+
+fn solve(inputs: &Inputs, day: usize, part: usize) -> Result<Answer, Error> {
+    let part_fn = find_part(day, part)?;
+    let input = inputs.get(day)?;
+
+    let now = Instant::now();
+    let answer = part_fn(&input).map_err(|source| Error::Solve { day, part, source })?;
+    let elapsed = now.elapsed();
 
-    Ok(())
+    Ok(Answer { answer, elapsed })
 }
 
-fn read_input(path: impl AsRef<Path>) -> Result<String, std::io::Error> {
-    std::fs::read_to_string(Path::new("input").join(path))
+fn export(inputs: &Inputs, day: usize, path: &Path) -> Result<(), Error> {
+    let export_fn = find_solution(day)?.export.ok_or(Error::NoExport(day))?;
+    let input = inputs.get(day)?;
+    export_fn(&input, path).map_err(|source| Error::Export { day, source })
 }
 
 mod my_nom_prelude {
     pub use crate::lib::nom_ext::complete::parse_isize;
     pub use crate::lib::nom_ext::complete::parse_usize;
+    pub use crate::lib::nom_ext::parse_all;
+    pub use crate::lib::nom_ext::parse_lines;
+    pub use crate::SolveError;
     pub use nom::branch::*;
     pub use nom::bytes::complete::*;
     pub use nom::character::complete::*;
-    pub use nom::character::*;
     pub use nom::combinator::*;
     pub use nom::multi::*;
     pub use nom::sequence::*;
@@ -149,6 +450,65 @@ mod my_nom_prelude {
 mod lib {
     /// Grid helpers for rectangular inputs
     pub mod grid {
+        /// Common interface of the grid implementations.
+        pub trait Grid {
+            type Tile;
+
+            fn get(&self, pos: Pos) -> Option<&Self::Tile>;
+
+            fn get_mut(&mut self, pos: Pos) -> Option<&mut Self::Tile>;
+
+            fn contains(&self, pos: Pos) -> bool {
+                self.get(pos).is_some()
+            }
+
+            /// Returns every position in the grid, with its tile.
+            fn iter(&self) -> impl Iterator<Item = (Pos, &Self::Tile)>;
+
+            /// Returns the smallest bounds containing every position in the grid.
+            fn bounds(&self) -> Bounds;
+
+            /// Returns the N, W, S and E neighbours of the position that are in the grid.
+            fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = (Pos, &Self::Tile)> {
+                pos.neighbours4().filter_map(|pos| self.get(pos).map(|tile| (pos, tile)))
+            }
+
+            /// Returns the neighbours of the position that are in the grid, including diagonal neighbours.
+            fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = (Pos, &Self::Tile)> {
+                pos.neighbours8().filter_map(|pos| self.get(pos).map(|tile| (pos, tile)))
+            }
+        }
+
+        /// Tile parsed from and rendered as a single character.
+        ///
+        /// Usually derived, with a `#[tile('c')]` attribute on each variant.
+        /// The derive also implements `Display` with [`GridTile::to_char`].
+        pub trait GridTile: Sized {
+            fn from_char(c: char) -> Option<Self>;
+
+            fn to_char(&self) -> char;
+        }
+        pub use grid_tile_derive::GridTile;
+
+        #[test]
+        fn derive_grid_tile() {
+            #[derive(Debug, PartialEq, GridTile)]
+            enum Tile {
+                #[tile('.')]
+                Empty,
+                #[tile('#')]
+                Wall,
+            }
+
+            assert_eq!(Tile::from_char('#'), Some(Tile::Wall));
+            assert_eq!(Tile::from_char('x'), None);
+            assert_eq!(Tile::Empty.to_char(), '.');
+
+            let grid = vec_of_vecs::Grid::<Tile>::parse_tiles("#.\n.#\n").unwrap();
+            assert_eq!(grid[Pos(0, 1)], Tile::Empty);
+            assert_eq!(render::Render::tiles(&grid).to_string(), "#.\n.#\n");
+        }
+
         pub mod hash_map {
             //! Grid implemented with `HashMap<Pos, Tile>`.
             use super::*;
@@ -170,6 +530,25 @@ mod lib {
                     &self.tiles
                 }
             }
+            impl<Tile> super::Grid for Grid<Tile> {
+                type Tile = Tile;
+
+                fn get(&self, pos: Pos) -> Option<&Tile> {
+                    self.tiles.get(&pos)
+                }
+
+                fn get_mut(&mut self, pos: Pos) -> Option<&mut Tile> {
+                    self.tiles.get_mut(&pos)
+                }
+
+                fn iter(&self) -> impl Iterator<Item = (Pos, &Tile)> {
+                    self.tiles.iter().map(|(pos, tile)| (*pos, tile))
+                }
+
+                fn bounds(&self) -> Bounds {
+                    self.bounds
+                }
+            }
             impl<Tile> Grid<Tile> {
                 pub fn new() -> Grid<Tile> {
                     Grid {
@@ -190,35 +569,6 @@ mod lib {
                     self.bounds.min_col = min(self.bounds.min_col, col);
                     self.bounds.max_col = max(self.bounds.max_col, col);
                 }
-
-                pub fn contains_pos(&self, pos: &Pos) -> bool {
-                    self.tiles.contains_key(pos)
-                }
-
-                pub fn bounds(&self) -> Bounds {
-                    self.bounds
-                }
-
-                #[allow(dead_code)]
-                pub fn dbg<F, S>(&self, fmt: F)
-                where
-                    F: Fn(Option<&Tile>) -> S,
-                    S: std::fmt::Display,
-                {
-                    let Bounds {
-                        min_row,
-                        max_row,
-                        min_col,
-                        max_col,
-                    } = self.bounds;
-                    for row in min_row..=max_row {
-                        for col in min_col..=max_col {
-                            let tile = self.tiles.get(&Pos(row, col));
-                            print!("{}", fmt(tile));
-                        }
-                        println!();
-                    }
-                }
             }
         }
 
@@ -226,6 +576,23 @@ mod lib {
             //! Grid implemented with `Vec<Vec<Tile>>`.
             use super::*;
 
+            /// Error from parsing a grid.
+            ///
+            /// Rows and columns are 0-indexed, like [`Pos`], but displayed 1-indexed as lines and columns,
+            /// like [`SolveError::Parse`](crate::SolveError::Parse).
+            #[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+            pub enum ParseError {
+                #[display(fmt = "Grid has no tiles")]
+                Empty,
+
+                #[display(fmt = "Line {} has {} tiles, expected {} like the first line", "row + 1", len, expected)]
+                Ragged { row: usize, len: usize, expected: usize },
+
+                #[display(fmt = "Invalid tile {:?} at line {}, column {}", c, "row + 1", "col + 1")]
+                InvalidTile { row: usize, col: usize, c: char },
+            }
+            impl std::error::Error for ParseError {}
+
             pub struct Grid<Tile> {
                 pub rows: Vec<Vec<Tile>>,
                 pub n_rows: usize,
@@ -254,72 +621,876 @@ mod lib {
                 }
             }
             impl<Tile> Grid<Tile> {
+                /// Parses a grid with a tile per character and a row per line.
+                ///
+                /// Panics if the input is not a valid grid, see [`Self::try_parse_char_grid`].
                 pub fn parse_char_grid<F>(input: &str, parse_tile: F) -> Self
                 where
                     F: Fn(char) -> Tile,
                 {
-                    let rows: Vec<Vec<Tile>> = input.lines().map(|line| line.chars().map(&parse_tile).collect()).collect();
+                    Self::try_parse_char_grid(input, |c| Some(parse_tile(c))).unwrap_or_else(|err| panic!("{err}"))
+                }
+
+                /// Parses a grid of tiles with [`GridTile::from_char`], see [`Self::try_parse_char_grid`].
+                pub fn parse_tiles(input: &str) -> Result<Self, ParseError>
+                where
+                    Tile: GridTile,
+                {
+                    Self::try_parse_char_grid(input, Tile::from_char)
+                }
+
+                /// Parses a grid with a tile per character and a row per line.
+                ///
+                /// Lines may end with `\n` or `\r\n`.
+                /// Fails if the input has no rows, if a row is not the same length as the first row,
+                /// or if `parse_tile` returns `None` for a character.
+                pub fn try_parse_char_grid<F>(input: &str, parse_tile: F) -> Result<Self, ParseError>
+                where
+                    F: Fn(char) -> Option<Tile>,
+                {
+                    crate::lib::parse_time::parsing(|| Self::parse_rows(input, parse_tile))
+                }
+
+                fn parse_rows<F>(input: &str, parse_tile: F) -> Result<Self, ParseError>
+                where
+                    F: Fn(char) -> Option<Tile>,
+                {
+                    let mut rows: Vec<Vec<Tile>> = Vec::new();
+                    for (row, line) in input.lines().enumerate() {
+                        let tiles = line
+                            .chars()
+                            .enumerate()
+                            .map(|(col, c)| parse_tile(c).ok_or(ParseError::InvalidTile { row, col, c }))
+                            .collect::<Result<Vec<Tile>, ParseError>>()?;
+                        if let Some(first) = rows.first() {
+                            if tiles.len() != first.len() {
+                                return Err(ParseError::Ragged {
+                                    row,
+                                    len: tiles.len(),
+                                    expected: first.len(),
+                                });
+                            }
+                        }
+                        rows.push(tiles);
+                    }
+
                     let n_rows = rows.len();
-                    let n_cols = rows[0].len();
-                    for row in &rows {
-                        assert_eq!(row.len(), n_cols, "At least one row is not the same length as the first row");
+                    let n_cols = rows.first().map_or(0, Vec::len);
+                    if n_cols == 0 {
+                        return Err(ParseError::Empty);
+                    }
+                    Ok(Grid { rows, n_rows, n_cols })
+                }
+
+                pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut Tile)> {
+                    self.rows.iter_mut().enumerate().flat_map(|(row, cols)| {
+                        cols.iter_mut()
+                            .enumerate()
+                            .map(move |(col, tile)| (Pos(row as isize, col as isize), tile))
+                    })
+                }
+
+                pub fn into_iter(self) -> impl Iterator<Item = (Pos, Tile)> {
+                    self.rows.into_iter().enumerate().flat_map(|(row, cols)| {
+                        cols.into_iter()
+                            .enumerate()
+                            .map(move |(col, tile)| (Pos(row as isize, col as isize), tile))
+                    })
+                }
+            }
+            impl<Tile> super::Grid for Grid<Tile> {
+                type Tile = Tile;
+
+                fn get(&self, pos: Pos) -> Option<&Tile> {
+                    if self.contains(pos) { Some(&self[pos]) } else { None }
+                }
+
+                fn get_mut(&mut self, pos: Pos) -> Option<&mut Tile> {
+                    if self.contains(pos) { Some(&mut self[pos]) } else { None }
+                }
+
+                fn contains(&self, pos: Pos) -> bool {
+                    let Pos(row, col) = pos;
+                    let n_rows = self.n_rows as isize;
+                    let n_cols = self.n_cols as isize;
+                    (0 <= row && row < n_rows) && (0 <= col && col < n_cols)
+                }
+
+                fn iter(&self) -> impl Iterator<Item = (Pos, &Tile)> {
+                    self.rows
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(row, cols)| cols.iter().enumerate().map(move |(col, tile)| (Pos(row as isize, col as isize), tile)))
+                }
+
+                fn bounds(&self) -> Bounds {
+                    Bounds {
+                        min_row: 0,
+                        max_row: self.n_rows as isize - 1,
+                        min_col: 0,
+                        max_col: self.n_cols as isize - 1,
+                    }
+                }
+            }
+            impl<Tile> Grid<Tile> {
+                /// Returns a view of the grid, which can be transformed without moving any tiles.
+                pub fn view(&self) -> View<&Self> {
+                    View::new(self)
+                }
+
+                /// Returns a mutable view of the grid, which can be transformed without moving any tiles.
+                pub fn view_mut(&mut self) -> View<&mut Self> {
+                    View::new(self)
+                }
+            }
+
+            /// One of the eight symmetries of a rectangle: an optional transpose, followed by optional flips.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct Transform {
+                pub transpose: bool,
+
+                /// Reverse the order of the rows (flip upside down).
+                pub flip_rows: bool,
+
+                /// Reverse the order of the columns (mirror left to right).
+                pub flip_cols: bool,
+            }
+            impl Transform {
+                pub fn transpose(self) -> Self {
+                    // Transposing swaps the axes of the flips.
+                    Self {
+                        transpose: !self.transpose,
+                        flip_rows: self.flip_cols,
+                        flip_cols: self.flip_rows,
                     }
-                    Grid { rows, n_rows, n_cols }
                 }
 
-                pub fn get(&self, pos: Pos) -> Option<&Tile> {
-                    if self.contains_pos(pos) { Some(&self[pos]) } else { None }
+                pub fn flip_rows(self) -> Self {
+                    Self {
+                        flip_rows: !self.flip_rows,
+                        ..self
+                    }
                 }
 
-                pub fn get_mut(&mut self, pos: Pos) -> Option<&mut Tile> {
-                    if self.contains_pos(pos) { Some(&mut self[pos]) } else { None }
+                pub fn flip_cols(self) -> Self {
+                    Self {
+                        flip_cols: !self.flip_cols,
+                        ..self
+                    }
+                }
+
+                pub fn rotate_cw(self) -> Self {
+                    self.transpose().flip_cols()
+                }
+
+                pub fn rotate_ccw(self) -> Self {
+                    self.transpose().flip_rows()
+                }
+
+                pub fn rotate_180(self) -> Self {
+                    self.flip_rows().flip_cols()
+                }
+            }
+
+            /// Shared or mutable reference to a grid, for [`View`].
+            pub trait GridRef {
+                type Tile;
+
+                fn grid(&self) -> &Grid<Self::Tile>;
+            }
+            pub trait GridMut: GridRef {
+                fn grid_mut(&mut self) -> &mut Grid<Self::Tile>;
+            }
+            impl<Tile> GridRef for &Grid<Tile> {
+                type Tile = Tile;
+
+                fn grid(&self) -> &Grid<Tile> {
+                    self
+                }
+            }
+            impl<Tile> GridRef for &mut Grid<Tile> {
+                type Tile = Tile;
+
+                fn grid(&self) -> &Grid<Tile> {
+                    self
+                }
+            }
+            impl<Tile> GridMut for &mut Grid<Tile> {
+                fn grid_mut(&mut self) -> &mut Grid<Tile> {
+                    self
+                }
+            }
+
+            /// Transformed view of a grid.
+            ///
+            /// Positions are in the transformed grid, and are mapped back to the underlying grid on access.
+            /// `R` is a reference to the underlying grid, shared or mutable.
+            pub struct View<R> {
+                grid: R,
+                transform: Transform,
+            }
+            impl<R: GridRef> View<R> {
+                pub fn new(grid: R) -> Self {
+                    Self {
+                        grid,
+                        transform: Transform::default(),
+                    }
+                }
+
+                pub fn with_transform(grid: R, transform: Transform) -> Self {
+                    Self { grid, transform }
+                }
+
+                pub fn transform(&self) -> Transform {
+                    self.transform
+                }
+
+                pub fn transpose(self) -> Self {
+                    self.map_transform(Transform::transpose)
+                }
+
+                pub fn flip_rows(self) -> Self {
+                    self.map_transform(Transform::flip_rows)
+                }
+
+                pub fn flip_cols(self) -> Self {
+                    self.map_transform(Transform::flip_cols)
+                }
+
+                pub fn rotate_cw(self) -> Self {
+                    self.map_transform(Transform::rotate_cw)
+                }
+
+                pub fn rotate_ccw(self) -> Self {
+                    self.map_transform(Transform::rotate_ccw)
+                }
+
+                pub fn rotate_180(self) -> Self {
+                    self.map_transform(Transform::rotate_180)
+                }
+
+                fn map_transform(self, f: impl FnOnce(Transform) -> Transform) -> Self {
+                    Self {
+                        grid: self.grid,
+                        transform: f(self.transform),
+                    }
+                }
+
+                pub fn n_rows(&self) -> usize {
+                    let grid = self.grid.grid();
+                    if self.transform.transpose { grid.n_cols } else { grid.n_rows }
+                }
+
+                pub fn n_cols(&self) -> usize {
+                    let grid = self.grid.grid();
+                    if self.transform.transpose { grid.n_rows } else { grid.n_cols }
+                }
+
+                /// Returns the position in the underlying grid of a position in the view.
+                pub fn base_pos(&self, pos: Pos) -> Pos {
+                    let Transform {
+                        transpose,
+                        flip_rows,
+                        flip_cols,
+                    } = self.transform;
+                    let Pos(mut row, mut col) = pos;
+                    if flip_rows {
+                        row = self.n_rows() as isize - 1 - row;
+                    }
+                    if flip_cols {
+                        col = self.n_cols() as isize - 1 - col;
+                    }
+                    if transpose { Pos(col, row) } else { Pos(row, col) }
+                }
+
+                pub fn contains(&self, pos: Pos) -> bool {
+                    let Pos(row, col) = pos;
+                    (0..self.n_rows() as isize).contains(&row) && (0..self.n_cols() as isize).contains(&col)
+                }
+
+                pub fn get(&self, pos: Pos) -> Option<&R::Tile> {
+                    if self.contains(pos) { Some(&self[pos]) } else { None }
+                }
+
+                /// Returns every position in the view, with its tile, row by row.
+                pub fn iter(&self) -> impl Iterator<Item = (Pos, &R::Tile)> {
+                    let n_cols = self.n_cols() as isize;
+                    (0..self.n_rows() as isize)
+                        .flat_map(move |row| (0..n_cols).map(move |col| Pos(row, col)))
+                        .map(|pos| (pos, &self[pos]))
+                }
+
+                /// Copies the view into a new grid.
+                pub fn to_grid(&self) -> Grid<R::Tile>
+                where
+                    R::Tile: Clone,
+                {
+                    let rows = (0..self.n_rows() as isize)
+                        .map(|row| (0..self.n_cols() as isize).map(|col| self[Pos(row, col)].clone()).collect())
+                        .collect();
+                    Grid {
+                        rows,
+                        n_rows: self.n_rows(),
+                        n_cols: self.n_cols(),
+                    }
+                }
+            }
+            impl<R: GridRef> std::ops::Index<Pos> for View<R> {
+                type Output = R::Tile;
+
+                fn index(&self, pos: Pos) -> &R::Tile {
+                    assert!(self.contains(pos), "{pos:?} is outside the view");
+                    &self.grid.grid()[self.base_pos(pos)]
+                }
+            }
+            impl<R: GridMut> std::ops::IndexMut<Pos> for View<R> {
+                fn index_mut(&mut self, pos: Pos) -> &mut R::Tile {
+                    assert!(self.contains(pos), "{pos:?} is outside the view");
+                    let base_pos = self.base_pos(pos);
+                    &mut self.grid.grid_mut()[base_pos]
+                }
+            }
+            impl<R: GridMut> super::Grid for View<R> {
+                type Tile = R::Tile;
+
+                fn get(&self, pos: Pos) -> Option<&R::Tile> {
+                    View::get(self, pos)
+                }
+
+                fn get_mut(&mut self, pos: Pos) -> Option<&mut R::Tile> {
+                    if self.contains(pos) { Some(&mut self[pos]) } else { None }
+                }
+
+                fn contains(&self, pos: Pos) -> bool {
+                    View::contains(self, pos)
+                }
+
+                fn iter(&self) -> impl Iterator<Item = (Pos, &R::Tile)> {
+                    View::iter(self)
+                }
+
+                fn bounds(&self) -> Bounds {
+                    Bounds {
+                        min_row: 0,
+                        max_row: self.n_rows() as isize - 1,
+                        min_col: 0,
+                        max_col: self.n_cols() as isize - 1,
+                    }
+                }
+            }
+
+            #[test]
+            fn try_parse_char_grid() {
+                let grid = Grid::try_parse_char_grid("ab\r\ncd\r\n", Some).unwrap();
+                assert_eq!((grid.n_rows, grid.n_cols), (2, 2));
+                assert_eq!(grid[Pos(1, 1)], 'd');
+
+                let digit = |c: char| c.to_digit(10);
+                assert_eq!(Grid::try_parse_char_grid("", digit).err(), Some(ParseError::Empty));
+                assert_eq!(Grid::try_parse_char_grid("\n", digit).err(), Some(ParseError::Empty));
+                assert_eq!(
+                    Grid::try_parse_char_grid("12\n345\n", digit).err(),
+                    Some(ParseError::Ragged { row: 1, len: 3, expected: 2 })
+                );
+                assert_eq!(
+                    Grid::try_parse_char_grid("12\n3x\n", digit).err(),
+                    Some(ParseError::InvalidTile { row: 1, col: 1, c: 'x' })
+                );
+            }
+
+            #[test]
+            fn parse_error_display() {
+                let err = Grid::try_parse_char_grid("12\n3x\n", |c: char| c.to_digit(10)).err().unwrap();
+                assert_eq!(err.to_string(), "Invalid tile 'x' at line 2, column 2");
+            }
+
+            #[test]
+            fn transforms() {
+                let grid = Grid::parse_char_grid("abc\ndef\n", |c| c);
+                let render = |view: View<&Grid<char>>| view.to_grid().rows.into_iter().map(String::from_iter).collect::<Vec<_>>().join("\n");
+
+                assert_eq!(render(grid.view()), "abc\ndef");
+                assert_eq!(render(grid.view().transpose()), "ad\nbe\ncf");
+                assert_eq!(render(grid.view().flip_rows()), "def\nabc");
+                assert_eq!(render(grid.view().flip_cols()), "cba\nfed");
+                assert_eq!(render(grid.view().rotate_cw()), "da\neb\nfc");
+                assert_eq!(render(grid.view().rotate_ccw()), "cf\nbe\nad");
+                assert_eq!(render(grid.view().rotate_180()), "fed\ncba");
+                assert_eq!(render(grid.view().rotate_cw().rotate_cw().rotate_cw().rotate_cw()), "abc\ndef");
+                assert_eq!(render(grid.view().rotate_cw().rotate_ccw()), "abc\ndef");
+                assert_eq!(render(grid.view().rotate_cw().flip_cols()), render(grid.view().transpose()));
+            }
+
+            #[test]
+            fn view_mut_writes_through() {
+                let mut grid = Grid::parse_char_grid("abc\ndef\n", |c| c);
+                let mut view = grid.view_mut().rotate_cw();
+                view[Pos(0, 0)] = 'X';
+                assert_eq!(view.get(Pos(2, 1)), Some(&'c'));
+                assert_eq!(view.get(Pos(3, 0)), None);
+                assert_eq!(grid[Pos(1, 0)], 'X');
+            }
+        }
+
+        pub mod bit_grid {
+            //! Grid of booleans, packed into a bit per tile.
+            use super::*;
+
+            /// Grid of booleans with a bit per tile, for sets of positions within fixed bounds.
+            ///
+            /// Each row is stored as whole words, so shifting and combining grids works a word at a time.
+            /// Bits outside the grid are always unset.
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            pub struct BitGrid {
+                words: Vec<u64>,
+                words_per_row: usize,
+                pub n_rows: usize,
+                pub n_cols: usize,
+            }
+            impl BitGrid {
+                /// Returns a grid with every bit unset.
+                pub fn new(n_rows: usize, n_cols: usize) -> Self {
+                    let words_per_row = n_cols.div_ceil(64);
+                    Self {
+                        words: vec![0; n_rows * words_per_row],
+                        words_per_row,
+                        n_rows,
+                        n_cols,
+                    }
+                }
+
+                /// Returns a grid with the bits set where `f` is true for the tile.
+                pub fn from_grid<Tile>(grid: &vec_of_vecs::Grid<Tile>, f: impl Fn(&Tile) -> bool) -> Self {
+                    let mut bits = Self::new(grid.n_rows, grid.n_cols);
+                    for (pos, tile) in grid.iter() {
+                        if f(tile) {
+                            bits.insert(pos);
+                        }
+                    }
+                    bits
+                }
+
+                pub fn contains(&self, pos: Pos) -> bool {
+                    self.index(pos).is_some_and(|(word, bit)| self.words[word] & bit != 0)
+                }
+
+                /// Sets the bit at the position, and returns whether it was unset.
+                ///
+                /// Panics if the position is outside the grid.
+                pub fn insert(&mut self, pos: Pos) -> bool {
+                    let (word, bit) = self.index(pos).unwrap_or_else(|| panic!("{pos:?} is outside the grid"));
+                    let was_unset = self.words[word] & bit == 0;
+                    self.words[word] |= bit;
+                    was_unset
+                }
+
+                /// Unsets the bit at the position, and returns whether it was set.
+                pub fn remove(&mut self, pos: Pos) -> bool {
+                    let Some((word, bit)) = self.index(pos) else {
+                        return false;
+                    };
+                    let was_set = self.words[word] & bit != 0;
+                    self.words[word] &= !bit;
+                    was_set
+                }
+
+                pub fn clear(&mut self) {
+                    self.words.fill(0);
+                }
+
+                /// Number of set bits.
+                pub fn count_ones(&self) -> usize {
+                    self.words.iter().map(|word| word.count_ones() as usize).sum()
+                }
+
+                pub fn is_empty(&self) -> bool {
+                    self.words.iter().all(|&word| word == 0)
+                }
+
+                /// Returns the positions of the set bits, in reading order.
+                pub fn iter(&self) -> impl Iterator<Item = Pos> + '_ {
+                    self.words.chunks(self.words_per_row).enumerate().flat_map(|(row, words)| {
+                        words.iter().enumerate().flat_map(move |(idx, &word)| {
+                            let mut word = word;
+                            std::iter::from_fn(move || {
+                                (word != 0).then(|| {
+                                    let bit = word.trailing_zeros() as usize;
+                                    word &= word - 1;
+                                    Pos(row as isize, (idx * 64 + bit) as isize)
+                                })
+                            })
+                        })
+                    })
+                }
+
+                /// Returns the grid with every set bit moved a step in the given direction.
+                ///
+                /// Bits moved off the grid are dropped.
+                pub fn shift(&self, dir: Dir) -> Self {
+                    let mut shifted = Self::new(self.n_rows, self.n_cols);
+                    let w = self.words_per_row;
+                    match dir {
+                        Dir::N => shifted.words[..self.words.len().saturating_sub(w)].copy_from_slice(&self.words[w.min(self.words.len())..]),
+                        Dir::S => shifted.words[w.min(self.words.len())..].copy_from_slice(&self.words[..self.words.len().saturating_sub(w)]),
+                        Dir::W => {
+                            for (row, shifted_row) in self.words.chunks(w).zip(shifted.words.chunks_mut(w)) {
+                                for idx in 0..w {
+                                    let carry = row.get(idx + 1).map_or(0, |next| next << 63);
+                                    shifted_row[idx] = (row[idx] >> 1) | carry;
+                                }
+                            }
+                        }
+                        Dir::E => {
+                            for (row, shifted_row) in self.words.chunks(w).zip(shifted.words.chunks_mut(w)) {
+                                for idx in 0..w {
+                                    let carry = if idx == 0 { 0 } else { row[idx - 1] >> 63 };
+                                    shifted_row[idx] = (row[idx] << 1) | carry;
+                                }
+                            }
+                            shifted.mask_last_col();
+                        }
+                    }
+                    shifted
+                }
+
+                /// Unsets the bits in `other`.
+                pub fn difference_with(&mut self, other: &BitGrid) {
+                    self.assert_same_size(other);
+                    for (word, other) in self.words.iter_mut().zip(&other.words) {
+                        *word &= !other;
+                    }
+                }
+
+                /// Returns the word and bit mask of the position, if it is in the grid.
+                fn index(&self, pos: Pos) -> Option<(usize, u64)> {
+                    let (row, col) = (usize::try_from(pos.row()).ok()?, usize::try_from(pos.col()).ok()?);
+                    (row < self.n_rows && col < self.n_cols).then(|| (row * self.words_per_row + col / 64, 1 << (col % 64)))
+                }
+
+                /// Unsets the bits past the last column, in the last word of each row.
+                fn mask_last_col(&mut self) {
+                    if self.n_cols.is_multiple_of(64) {
+                        return;
+                    }
+                    let mask = (1 << (self.n_cols % 64)) - 1;
+                    for row in self.words.chunks_mut(self.words_per_row) {
+                        *row.last_mut().unwrap() &= mask;
+                    }
+                }
+
+                fn assert_same_size(&self, other: &BitGrid) {
+                    assert_eq!((self.n_rows, self.n_cols), (other.n_rows, other.n_cols), "Grids are not the same size");
+                }
+            }
+            impl std::ops::BitOrAssign<&BitGrid> for BitGrid {
+                fn bitor_assign(&mut self, other: &BitGrid) {
+                    self.assert_same_size(other);
+                    for (word, other) in self.words.iter_mut().zip(&other.words) {
+                        *word |= other;
+                    }
+                }
+            }
+            impl std::ops::BitAndAssign<&BitGrid> for BitGrid {
+                fn bitand_assign(&mut self, other: &BitGrid) {
+                    self.assert_same_size(other);
+                    for (word, other) in self.words.iter_mut().zip(&other.words) {
+                        *word &= other;
+                    }
+                }
+            }
+            impl std::ops::BitOr for &BitGrid {
+                type Output = BitGrid;
+
+                fn bitor(self, other: &BitGrid) -> BitGrid {
+                    let mut union = self.clone();
+                    union |= other;
+                    union
+                }
+            }
+            impl std::ops::BitAnd for &BitGrid {
+                type Output = BitGrid;
+
+                fn bitand(self, other: &BitGrid) -> BitGrid {
+                    let mut intersection = self.clone();
+                    intersection &= other;
+                    intersection
+                }
+            }
+
+            #[test]
+            fn bit_grid() {
+                // Wider than a word, to shift across word boundaries.
+                let mut bits = BitGrid::new(3, 70);
+                assert!(bits.insert(Pos(0, 63)));
+                assert!(!bits.insert(Pos(0, 63)));
+                bits.insert(Pos(1, 69));
+                bits.insert(Pos(2, 0));
+                assert!(!bits.contains(Pos(-1, 0)));
+                assert!(!bits.contains(Pos(0, 70)));
+                assert_eq!(bits.count_ones(), 3);
+                assert_eq!(bits.iter().collect::<Vec<_>>(), [Pos(0, 63), Pos(1, 69), Pos(2, 0)]);
+
+                assert_eq!(bits.shift(Dir::E).iter().collect::<Vec<_>>(), [Pos(0, 64), Pos(2, 1)]);
+                assert_eq!(bits.shift(Dir::W).iter().collect::<Vec<_>>(), [Pos(0, 62), Pos(1, 68)]);
+                assert_eq!(bits.shift(Dir::N).iter().collect::<Vec<_>>(), [Pos(0, 69), Pos(1, 0)]);
+                assert_eq!(bits.shift(Dir::S).iter().collect::<Vec<_>>(), [Pos(1, 63), Pos(2, 69)]);
+                assert_eq!(bits.shift(Dir::E).shift(Dir::W).count_ones(), 2);
+
+                let mut other = BitGrid::new(3, 70);
+                other.insert(Pos(0, 63));
+                other.insert(Pos(1, 1));
+                assert_eq!((&bits | &other).count_ones(), 4);
+                assert_eq!((&bits & &other).iter().collect::<Vec<_>>(), [Pos(0, 63)]);
+
+                bits.difference_with(&other);
+                assert!(bits.remove(Pos(1, 69)));
+                assert!(!bits.remove(Pos(1, 69)));
+                assert_eq!(bits.iter().collect::<Vec<_>>(), [Pos(2, 0)]);
+                bits.clear();
+                assert!(bits.is_empty());
+            }
+        }
+
+        pub mod render {
+            //! Rendering of grids as text, with overlays drawn on top of the tiles.
+            use super::*;
+            use colored::Color;
+            use colored::Colorize as _;
+            use std::borrow::Borrow;
+            use std::collections::HashMap;
+
+            type TileFn<'a, Tile> = Box<dyn Fn(Pos, Option<&Tile>) -> char + 'a>;
+
+            /// Renders a grid through `Display`.
+            ///
+            /// The rendered area covers the bounds of the grid and every overlaid position,
+            /// so overlays are not cut off at the edge of a sparse grid.
+            pub struct Render<'a, G: Grid> {
+                grid: &'a G,
+                tile: TileFn<'a, G::Tile>,
+                overlay: HashMap<Pos, (char, Color)>,
+                colour: bool,
+            }
+            impl<'a, G: Grid> Render<'a, G> {
+                /// Renders each tile as the character returned by `tile`, which gets `None` for positions without a tile.
+                pub fn new(grid: &'a G, tile: impl Fn(Pos, Option<&G::Tile>) -> char + 'a) -> Self {
+                    Self {
+                        grid,
+                        tile: Box::new(tile),
+                        overlay: HashMap::new(),
+                        colour: false,
+                    }
+                }
+
+                /// Renders each tile with [`GridTile::to_char`], and positions without a tile as spaces.
+                pub fn tiles(grid: &'a G) -> Self
+                where
+                    G::Tile: GridTile,
+                {
+                    Self::new(grid, |_pos, tile| tile.map_or(' ', GridTile::to_char))
+                }
+
+                /// Sets whether overlays are coloured with ANSI escape codes.
+                pub fn colour(mut self, colour: bool) -> Self {
+                    self.colour = colour;
+                    self
+                }
+
+                /// Draws `c` at each of the positions.
+                ///
+                /// Later overlays are drawn on top of earlier overlays.
+                pub fn overlay(mut self, positions: impl IntoIterator<Item = impl Borrow<Pos>>, c: char, colour: Color) -> Self {
+                    for pos in positions {
+                        self.overlay.insert(*pos.borrow(), (c, colour));
+                    }
+                    self
+                }
+
+                /// Draws the path through the points with arrows.
+                ///
+                /// Consecutive points must lie on the same row or column, the positions between them are drawn as well.
+                pub fn path(mut self, points: impl IntoIterator<Item = impl Borrow<Pos>>, colour: Color) -> Self {
+                    let mut points = points.into_iter().map(|pos| *pos.borrow());
+                    let Some(mut from) = points.next() else {
+                        return self;
+                    };
+                    let mut last_dir = None;
+                    for to in points {
+                        if to == from {
+                            continue;
+                        }
+                        let dir = Dir::from_positions(from, to).expect("Path segments must be horizontal or vertical");
+                        let mut pos = from;
+                        while pos != to {
+                            self.overlay.insert(pos, (arrow(dir), colour));
+                            pos = pos.step(dir);
+                        }
+                        (from, last_dir) = (to, Some(dir));
+                    }
+                    if let Some(dir) = last_dir {
+                        self.overlay.insert(from, (arrow(dir), colour));
+                    }
+                    self
+                }
+
+                fn bounds(&self) -> Bounds {
+                    self.overlay.keys().fold(self.grid.bounds(), |bounds, pos| Bounds {
+                        min_row: bounds.min_row.min(pos.row()),
+                        max_row: bounds.max_row.max(pos.row()),
+                        min_col: bounds.min_col.min(pos.col()),
+                        max_col: bounds.max_col.max(pos.col()),
+                    })
+                }
+            }
+            impl<G: Grid> std::fmt::Display for Render<'_, G> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    let Bounds {
+                        min_row,
+                        max_row,
+                        min_col,
+                        max_col,
+                    } = self.bounds();
+                    for row in min_row..=max_row {
+                        for col in min_col..=max_col {
+                            let pos = Pos(row, col);
+                            match self.overlay.get(&pos) {
+                                Some(&(c, colour)) if self.colour => write!(f, "{}", c.to_string().color(colour))?,
+                                Some(&(c, _colour)) => write!(f, "{c}")?,
+                                None => write!(f, "{}", (self.tile)(pos, self.grid.get(pos)))?,
+                            }
+                        }
+                        writeln!(f)?;
+                    }
+                    Ok(())
+                }
+            }
+
+            fn arrow(dir: Dir) -> char {
+                match dir {
+                    Dir::N => '^',
+                    Dir::W => '<',
+                    Dir::S => 'v',
+                    Dir::E => '>',
+                }
+            }
+
+            #[test]
+            fn render() {
+                let grid = vec_of_vecs::Grid::parse_char_grid("...\n.#.\n", |c| c);
+                let reached = std::collections::HashSet::from([Pos(0, 0), Pos(1, 2)]);
+                let render = Render::new(&grid, |_pos, tile| *tile.unwrap()).overlay(&reached, 'O', Color::Green);
+                assert_eq!(render.to_string(), "O..\n.#O\n");
+
+                let render = render.path([Pos(0, 0), Pos(0, 2), Pos(1, 2)], Color::Red);
+                assert_eq!(render.to_string(), ">>v\n.#v\n");
+
+                colored::control::set_override(true);
+                let coloured = render.colour(true).to_string();
+                colored::control::unset_override();
+                assert!(coloured.contains(&format!("{}", ">".color(Color::Red))));
+            }
+
+            #[test]
+            fn render_sparse() {
+                let mut grid = hash_map::Grid::new();
+                grid.insert(Pos(-1, -1), '#');
+                grid.insert(Pos(0, 1), '#');
+                let render = Render::new(&grid, |_pos, tile| tile.copied().unwrap_or('.')).overlay([Pos(1, 0)], 'O', Color::Blue);
+                assert_eq!(render.to_string(), "#..\n..#\n.O.\n");
+            }
+        }
+
+        pub mod wrapping {
+            //! Grid repeated infinitely in every direction.
+            use super::*;
+
+            /// Position in a repeated grid, split into the copy of the grid it is in and the position within that copy.
+            ///
+            /// The copy containing the bounds of the underlying grid is `Pos(0, 0)`, the copy to its left is `Pos(0, -1)`, and so on.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct Wrapped {
+                pub copy: Pos,
+                pub pos: Pos,
+            }
+
+            /// Adapter repeating a grid infinitely in every direction.
+            pub struct Wrapping<'a, G> {
+                grid: &'a G,
+                bounds: Bounds,
+            }
+            impl<'a, G: Grid> Wrapping<'a, G> {
+                pub fn new(grid: &'a G) -> Self {
+                    Self { grid, bounds: grid.bounds() }
                 }
 
-                pub fn contains_pos(&self, pos: Pos) -> bool {
-                    let Pos(row, col) = pos;
-                    let n_rows = self.n_rows as isize;
-                    let n_cols = self.n_cols as isize;
-                    (0 <= row && row < n_rows) && (0 <= col && col < n_cols)
+                /// Maps a position to the position in the underlying grid, and the copy of the grid it is in.
+                pub fn wrap(&self, pos: Pos) -> Wrapped {
+                    let Bounds {
+                        min_row,
+                        max_row,
+                        min_col,
+                        max_col,
+                    } = self.bounds;
+                    let (n_rows, n_cols) = (max_row - min_row + 1, max_col - min_col + 1);
+                    let (row, col) = (pos.row() - min_row, pos.col() - min_col);
+                    Wrapped {
+                        copy: Pos(row.div_euclid(n_rows), col.div_euclid(n_cols)),
+                        pos: Pos(min_row + row.rem_euclid(n_rows), min_col + col.rem_euclid(n_cols)),
+                    }
                 }
 
-                pub fn iter(&self) -> impl Iterator<Item = (Pos, &Tile)> {
-                    self.rows
-                        .iter()
-                        .enumerate()
-                        .flat_map(|(row, cols)| cols.iter().enumerate().map(move |(col, tile)| (Pos(row as isize, col as isize), tile)))
+                /// Returns the tile at the position, which is `None` only if the underlying grid has no tile there.
+                pub fn get(&self, pos: Pos) -> Option<&'a G::Tile> {
+                    self.grid.get(self.wrap(pos).pos)
                 }
 
-                pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut Tile)> {
-                    self.rows.iter_mut().enumerate().flat_map(|(row, cols)| {
-                        cols.iter_mut()
-                            .enumerate()
-                            .map(move |(col, tile)| (Pos(row as isize, col as isize), tile))
-                    })
+                /// Returns the N, W, S and E neighbours of the position.
+                pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = (Pos, &'a G::Tile)> + '_ {
+                    pos.neighbours4().filter_map(|pos| self.get(pos).map(|tile| (pos, tile)))
                 }
+            }
+            impl<G: Grid> std::ops::Index<Pos> for Wrapping<'_, G> {
+                type Output = G::Tile;
 
-                pub fn into_iter(self) -> impl Iterator<Item = (Pos, Tile)> {
-                    self.rows.into_iter().enumerate().flat_map(|(row, cols)| {
-                        cols.into_iter()
-                            .enumerate()
-                            .map(move |(col, tile)| (Pos(row as isize, col as isize), tile))
-                    })
+                fn index(&self, pos: Pos) -> &G::Tile {
+                    let wrapped = self.wrap(pos);
+                    self.get(pos).unwrap_or_else(|| panic!("No tile at {:?}", wrapped.pos))
                 }
+            }
 
-                #[allow(dead_code)]
-                pub fn dbg<F, S>(&self, fmt: F)
-                where
-                    F: Fn(Pos, &Tile) -> S,
-                    S: std::fmt::Display,
-                {
-                    for (row_idx, row) in self.rows.iter().enumerate() {
-                        for (col_idx, tile) in row.iter().enumerate() {
-                            let pos = Pos(row_idx as isize, col_idx as isize);
-                            print!("{}", fmt(pos, tile));
-                        }
-                        println!();
+            #[test]
+            fn wrap() {
+                let grid = vec_of_vecs::Grid::parse_char_grid("abc\ndef\n", |c| c);
+                let wrapping = Wrapping::new(&grid);
+
+                assert_eq!(wrapping[Pos(0, 0)], 'a');
+                assert_eq!(wrapping[Pos(-1, -1)], 'f');
+                assert_eq!(wrapping[Pos(5, 7)], 'e');
+                assert_eq!(
+                    wrapping.wrap(Pos(-1, -4)),
+                    Wrapped {
+                        copy: Pos(-1, -2),
+                        pos: Pos(1, 2)
                     }
+                );
+                for pos in [Pos(0, 0), Pos(-1, -4), Pos(5, 7), Pos(-7, 3)] {
+                    let Wrapped { copy, pos: base } = wrapping.wrap(pos);
+                    assert_eq!(Pos(copy.row() * 2 + base.row(), copy.col() * 3 + base.col()), pos);
                 }
             }
+
+            #[test]
+            fn wrap_offset_bounds() {
+                let mut grid = hash_map::Grid::new();
+                grid.insert(Pos(0, 0), 'a');
+                grid.insert(Pos(-1, 1), 'b');
+                let wrapping = Wrapping::new(&grid);
+
+                assert_eq!(wrapping.get(Pos(1, 1)), Some(&'b'));
+                assert_eq!(wrapping.get(Pos(2, -2)), Some(&'a'));
+                assert_eq!(wrapping.get(Pos(1, 0)), None);
+                assert_eq!(wrapping.wrap(Pos(1, 0)).copy, Pos(1, 0));
+            }
         }
 
         /// Row, Column.
@@ -329,7 +1500,26 @@ mod lib {
         /// |
         /// |       X <- here
         /// |
-        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        ///
+        /// Positions add and subtract as vectors, and are ordered by row, then column.
+        #[derive(
+            Clone,
+            Copy,
+            Debug,
+            PartialEq,
+            Eq,
+            Hash,
+            PartialOrd,
+            Ord,
+            derive_more::Add,
+            derive_more::Sub,
+            derive_more::Neg,
+            derive_more::Mul,
+            derive_more::AddAssign,
+            derive_more::SubAssign,
+            derive_more::From,
+            derive_more::Into,
+        )]
         pub struct Pos(pub isize, pub isize);
         impl Pos {
             pub fn row(&self) -> isize {
@@ -367,36 +1557,20 @@ mod lib {
                     Dir::E => Pos(row, col + n),
                 }
             }
-        }
 
-        /// Relative direction.
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-        pub enum RelDir {
-            Front,
-            Left,
-            Right,
-            Behind,
-        }
-        impl RelDir {
-            pub fn from_dirs(from: Dir, to: Dir) -> RelDir {
-                match (from, to) {
-                    (Dir::N, Dir::N) => RelDir::Front,
-                    (Dir::N, Dir::W) => RelDir::Left,
-                    (Dir::N, Dir::S) => RelDir::Behind,
-                    (Dir::N, Dir::E) => RelDir::Right,
-                    (Dir::W, Dir::N) => RelDir::Right,
-                    (Dir::W, Dir::W) => RelDir::Front,
-                    (Dir::W, Dir::S) => RelDir::Left,
-                    (Dir::W, Dir::E) => RelDir::Behind,
-                    (Dir::S, Dir::N) => RelDir::Behind,
-                    (Dir::S, Dir::W) => RelDir::Right,
-                    (Dir::S, Dir::S) => RelDir::Front,
-                    (Dir::S, Dir::E) => RelDir::Left,
-                    (Dir::E, Dir::N) => RelDir::Left,
-                    (Dir::E, Dir::W) => RelDir::Behind,
-                    (Dir::E, Dir::S) => RelDir::Right,
-                    (Dir::E, Dir::E) => RelDir::Front,
-                }
+            /// Returns a new position a single step in the given direction, which may be diagonal.
+            pub fn step8(&self, dir: Dir8) -> Pos {
+                *self + Pos::from(dir.offset())
+            }
+
+            /// Returns the N, W, S and E neighbours.
+            pub fn neighbours4(self) -> impl Iterator<Item = Pos> {
+                Dir::every_direction().into_iter().map(move |dir| self.step(dir))
+            }
+
+            /// Returns the neighbours, including diagonal neighbours.
+            pub fn neighbours8(self) -> impl Iterator<Item = Pos> {
+                Dir8::every_direction().into_iter().map(move |dir| self.step8(dir))
             }
         }
 
@@ -443,6 +1617,53 @@ mod lib {
             }
         }
 
+        /// Direction, including the diagonal directions.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Dir8 {
+            N,
+            NE,
+            E,
+            SE,
+            S,
+            SW,
+            W,
+            NW,
+        }
+        impl Dir8 {
+            /// Every direction, clockwise from N.
+            pub fn every_direction() -> [Dir8; 8] {
+                [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW]
+            }
+
+            /// Returns the change in (row, column) of a step in this direction.
+            pub fn offset(&self) -> (isize, isize) {
+                match self {
+                    Dir8::N => (-1, 0),
+                    Dir8::NE => (-1, 1),
+                    Dir8::E => (0, 1),
+                    Dir8::SE => (1, 1),
+                    Dir8::S => (1, 0),
+                    Dir8::SW => (1, -1),
+                    Dir8::W => (0, -1),
+                    Dir8::NW => (-1, -1),
+                }
+            }
+
+            pub fn is_diagonal(&self) -> bool {
+                matches!(self, Dir8::NE | Dir8::SE | Dir8::SW | Dir8::NW)
+            }
+        }
+        impl From<Dir> for Dir8 {
+            fn from(dir: Dir) -> Self {
+                match dir {
+                    Dir::N => Dir8::N,
+                    Dir::W => Dir8::W,
+                    Dir::S => Dir8::S,
+                    Dir::E => Dir8::E,
+                }
+            }
+        }
+
         #[derive(Clone, Copy, PartialEq, Eq, Hash)]
         pub struct PosDir {
             pub pos: Pos,
@@ -475,13 +1696,372 @@ mod lib {
             }
         }
 
-        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        #[test]
+        fn pos_arithmetic() {
+            let mut pos = Pos(2, 8) + Pos(1, -3);
+            assert_eq!(pos, Pos(3, 5));
+            assert_eq!(pos - Pos(3, 5), Pos(0, 0));
+            assert_eq!(-pos, Pos(-3, -5));
+            assert_eq!(pos * 2, Pos(6, 10));
+            pos -= Pos(1, 1);
+            pos += Pos(0, 2);
+            assert_eq!(<(isize, isize)>::from(pos), (2, 6));
+            assert_eq!(Pos::from((2, 6)), pos);
+
+            let mut sorted = vec![Pos(1, 0), Pos(0, 5), Pos(0, -1)];
+            sorted.sort();
+            assert_eq!(sorted, [Pos(0, -1), Pos(0, 5), Pos(1, 0)]);
+        }
+
+        /// Inclusive bounds.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
         pub struct Bounds {
             pub min_row: isize,
             pub max_row: isize,
             pub min_col: isize,
             pub max_col: isize,
         }
+
+        #[test]
+        fn backings_agree() {
+            fn neighbours(grid: &impl Grid<Tile = char>, pos: Pos) -> Vec<(Pos, char)> {
+                let mut neighbours: Vec<(Pos, char)> = grid.neighbours4(pos).map(|(pos, &tile)| (pos, tile)).collect();
+                neighbours.sort_by_key(|(pos, _tile)| (pos.row(), pos.col()));
+                neighbours
+            }
+
+            let input = "#.\n.#\n";
+            let vec_grid = vec_of_vecs::Grid::parse_char_grid(input, |c| c);
+            let mut map_grid = hash_map::Grid::new();
+            for (pos, &tile) in vec_grid.iter() {
+                map_grid.insert(pos, tile);
+            }
+
+            assert_eq!(vec_grid.bounds(), map_grid.bounds());
+            assert_eq!(render::Render::new(&vec_grid, |_pos, tile| *tile.unwrap()).to_string(), input);
+            assert_eq!(render::Render::new(&map_grid, |_pos, tile| *tile.unwrap()).to_string(), input);
+            assert_eq!(neighbours(&vec_grid, Pos(0, 0)), vec![(Pos(0, 1), '.'), (Pos(1, 0), '.')]);
+            assert_eq!(neighbours(&map_grid, Pos(0, 0)), neighbours(&vec_grid, Pos(0, 0)));
+            assert!(!vec_grid.contains(Pos(2, 0)));
+            assert!(!map_grid.contains(Pos(2, 0)));
+        }
+
+        #[test]
+        fn bounded_neighbours() {
+            let grid = vec_of_vecs::Grid::parse_char_grid("abc\ndef\nghi\n", |c| c);
+            let neighbours8 = |pos| {
+                let mut tiles: Vec<char> = grid.neighbours8(pos).map(|(_pos, &tile)| tile).collect();
+                tiles.sort();
+                String::from_iter(tiles)
+            };
+
+            assert_eq!(neighbours8(Pos(1, 1)), "abcdfghi");
+            assert_eq!(neighbours8(Pos(0, 0)), "bde");
+            assert_eq!(neighbours8(Pos(2, 1)), "defgi");
+            assert_eq!(grid.neighbours4(Pos(0, 2)).count(), 2);
+        }
+    }
+
+    pub mod geometry {
+        //! Polygons with vertices on integer positions.
+        //!
+        //! Polygons are given as their vertices in order, and are closed from the last vertex back to the first.
+        //! Edges may be axis-aligned or not, and vertices may lie on a straight line.
+        use super::grid::Dir;
+        use super::grid::Pos;
+
+        /// Returns twice the signed area of the polygon, positive if it is clockwise (shoelace formula).
+        ///
+        /// Twice the area, so that it is a whole number.
+        pub fn twice_signed_area(vertices: &[Pos]) -> isize {
+            edges(vertices).map(|(a, b)| a.col() * b.row() - b.col() * a.row()).sum()
+        }
+
+        /// Returns the number of integer positions on the edges of the polygon.
+        pub fn boundary_points(vertices: &[Pos]) -> isize {
+            edges(vertices)
+                .map(|(a, b)| gcd::Gcd::gcd((b.row() - a.row()).unsigned_abs(), (b.col() - a.col()).unsigned_abs()) as isize)
+                .sum()
+        }
+
+        /// Returns the number of integer positions strictly inside the polygon (Pick's theorem).
+        ///
+        /// The polygon must not intersect itself.
+        pub fn interior_points(vertices: &[Pos]) -> isize {
+            // Pick's theorem: area = interior + boundary / 2 - 1
+            (twice_signed_area(vertices).abs() - boundary_points(vertices) + 2) / 2
+        }
+
+        /// Returns the vertices of the rectilinear polygon walked from `start` by taking the given number of steps in each direction.
+        ///
+        /// The walk is expected to end where it started, the last vertex is left out as it is the same as the first.
+        pub fn walk(start: Pos, moves: impl IntoIterator<Item = (Dir, isize)>) -> Vec<Pos> {
+            let mut vertices = vec![start];
+            for (dir, steps) in moves {
+                let pos = vertices.last().unwrap().steps(steps, dir);
+                vertices.push(pos);
+            }
+            if vertices.len() > 1 && vertices.last() == Some(&start) {
+                vertices.pop();
+            }
+            vertices
+        }
+
+        fn edges(vertices: &[Pos]) -> impl Iterator<Item = (Pos, Pos)> + '_ {
+            vertices.iter().zip(vertices.iter().cycle().skip(1)).map(|(&a, &b)| (a, b))
+        }
+
+        #[test]
+        fn rectangle() {
+            let vertices = walk(Pos(0, 0), [(Dir::E, 4), (Dir::S, 2), (Dir::W, 4), (Dir::N, 2)]);
+            assert_eq!(vertices, [Pos(0, 0), Pos(0, 4), Pos(2, 4), Pos(2, 0)]);
+            assert_eq!(twice_signed_area(&vertices), 16);
+            assert_eq!(boundary_points(&vertices), 12);
+            assert_eq!(interior_points(&vertices), 3);
+
+            let reversed: Vec<Pos> = vertices.iter().rev().copied().collect();
+            assert_eq!(twice_signed_area(&reversed), -16);
+            assert_eq!(interior_points(&reversed), 3);
+        }
+
+        #[test]
+        fn triangle() {
+            let vertices = [Pos(0, 0), Pos(0, 4), Pos(4, 0)];
+            assert_eq!(twice_signed_area(&vertices), 16);
+            assert_eq!(boundary_points(&vertices), 12);
+            assert_eq!(interior_points(&vertices), 3);
+
+            assert_eq!(twice_signed_area(&[Pos(0, 0), Pos(0, 4), Pos(0, 2)]), 0);
+        }
+    }
+
+    pub mod space {
+        //! Positions and cuboids in three dimensions.
+
+        /// Position in space.
+        ///
+        /// Positions add and subtract as vectors, and are ordered by x, then y, then z.
+        #[derive(
+            Clone,
+            Copy,
+            Debug,
+            PartialEq,
+            Eq,
+            Hash,
+            PartialOrd,
+            Ord,
+            derive_more::Add,
+            derive_more::Sub,
+            derive_more::Neg,
+            derive_more::Mul,
+            derive_more::AddAssign,
+            derive_more::SubAssign,
+            derive_more::From,
+            derive_more::Into,
+        )]
+        pub struct Pos3 {
+            pub x: isize,
+            pub y: isize,
+            pub z: isize,
+        }
+        impl Pos3 {
+            pub fn manhattan_distance(&self, to: Pos3) -> isize {
+                (self.x - to.x).abs() + (self.y - to.y).abs() + (self.z - to.z).abs()
+            }
+        }
+
+        /// Axis-aligned cuboid, spanning from `min` to `max` (inclusive).
+        ///
+        /// Every coordinate of `min` is less than or equal to that of `max`.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub struct Cuboid {
+            pub min: Pos3,
+            pub max: Pos3,
+        }
+        impl Cuboid {
+            /// Returns the cuboid spanning between two opposite corners.
+            pub fn new(a: Pos3, b: Pos3) -> Self {
+                Self {
+                    min: Pos3 {
+                        x: a.x.min(b.x),
+                        y: a.y.min(b.y),
+                        z: a.z.min(b.z),
+                    },
+                    max: Pos3 {
+                        x: a.x.max(b.x),
+                        y: a.y.max(b.y),
+                        z: a.z.max(b.z),
+                    },
+                }
+            }
+        }
+
+        #[test]
+        fn pos3_arithmetic() {
+            let a = Pos3 { x: 1, y: 2, z: 3 };
+            let b = Pos3::from((4, -1, 0));
+            assert_eq!(a + b, Pos3 { x: 5, y: 1, z: 3 });
+            assert_eq!(a - b, Pos3 { x: -3, y: 3, z: 3 });
+            assert_eq!(-a * 2, Pos3 { x: -2, y: -4, z: -6 });
+            assert_eq!(<(isize, isize, isize)>::from(a), (1, 2, 3));
+            assert!(a < b);
+            assert_eq!(a.manhattan_distance(b), 9);
+        }
+
+        #[test]
+        fn cuboid_corners() {
+            let cuboid = Cuboid::new(Pos3 { x: 2, y: 0, z: 5 }, Pos3 { x: 1, y: 3, z: 4 });
+            assert_eq!(cuboid.min, Pos3 { x: 1, y: 0, z: 4 });
+            assert_eq!(cuboid.max, Pos3 { x: 2, y: 3, z: 5 });
+        }
+    }
+
+    pub mod export {
+        //! Export of grids to images, and of paths to SVG, for looking at inputs that are too big for the terminal.
+        use super::grid::vec_of_vecs;
+        use super::grid::Grid as _;
+        use super::grid::Pos;
+        use std::io::Write;
+        use std::path::Path;
+
+        pub type Rgb = [u8; 3];
+
+        /// Image with a pixel per tile, or a square of pixels per tile if scaled.
+        pub struct Image {
+            width: usize,
+            height: usize,
+            pixels: Vec<Rgb>,
+        }
+        impl Image {
+            pub fn from_grid<Tile>(grid: &vec_of_vecs::Grid<Tile>, colour: impl Fn(Pos, &Tile) -> Rgb) -> Self {
+                Self {
+                    width: grid.n_cols,
+                    height: grid.n_rows,
+                    pixels: grid.iter().map(|(pos, tile)| colour(pos, tile)).collect(),
+                }
+            }
+
+            /// Scales the image up, drawing each pixel as a `factor` × `factor` square.
+            pub fn scale(self, factor: usize) -> Self {
+                let (width, height) = (self.width * factor, self.height * factor);
+                let pixels = (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x / factor, y / factor)))
+                    .map(|(x, y)| self.pixels[y * self.width + x])
+                    .collect();
+                Self { width, height, pixels }
+            }
+
+            /// Writes the image as a binary PPM.
+            pub fn write_ppm(&self, mut w: impl Write) -> std::io::Result<()> {
+                write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+                w.write_all(self.pixels.as_flattened())
+            }
+
+            pub fn write_png(&self, w: impl Write) -> std::io::Result<()> {
+                let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32);
+                encoder.set_color(png::ColorType::Rgb);
+                encoder.set_depth(png::BitDepth::Eight);
+                let mut writer = encoder.write_header()?;
+                writer.write_image_data(self.pixels.as_flattened())?;
+                writer.finish()?;
+                Ok(())
+            }
+
+            /// Saves the image as PNG if the path ends with `.png`, and as PPM otherwise.
+            pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+                let path = path.as_ref();
+                let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                match path.extension() {
+                    Some(ext) if ext == "png" => self.write_png(file),
+                    _ => self.write_ppm(file),
+                }
+            }
+        }
+
+        /// SVG drawing of polylines through positions.
+        ///
+        /// Columns are drawn along the x axis and rows along the y axis, and the view box fits every point.
+        #[derive(Default)]
+        pub struct Svg {
+            polylines: Vec<(Vec<Pos>, String)>,
+        }
+        impl Svg {
+            /// Adds a polyline through the points, drawn with the given stroke colour (any SVG colour, like `"red"` or `"#ff0000"`).
+            pub fn polyline(mut self, points: impl IntoIterator<Item = Pos>, stroke: &str) -> Self {
+                self.polylines.push((points.into_iter().collect(), stroke.to_owned()));
+                self
+            }
+
+            pub fn write(&self, mut w: impl Write) -> std::io::Result<()> {
+                let points = || self.polylines.iter().flat_map(|(points, _stroke)| points);
+                let min_x = points().map(|pos| pos.col()).min().unwrap_or(0);
+                let max_x = points().map(|pos| pos.col()).max().unwrap_or(0);
+                let min_y = points().map(|pos| pos.row()).min().unwrap_or(0);
+                let max_y = points().map(|pos| pos.row()).max().unwrap_or(0);
+
+                // Pad by half a tile, so lines along the edges are not cut in half.
+                writeln!(
+                    w,
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+                    min_x as f64 - 0.5,
+                    min_y as f64 - 0.5,
+                    max_x - min_x + 1,
+                    max_y - min_y + 1
+                )?;
+                for (points, stroke) in &self.polylines {
+                    let points = points
+                        .iter()
+                        .map(|pos| format!("{},{}", pos.col(), pos.row()))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    writeln!(
+                        w,
+                        r#"  <polyline points="{points}" fill="none" stroke="{stroke}" stroke-width="1" vector-effect="non-scaling-stroke"/>"#
+                    )?;
+                }
+                writeln!(w, "</svg>")
+            }
+
+            pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+                self.write(std::io::BufWriter::new(std::fs::File::create(path)?))
+            }
+        }
+
+        #[test]
+        fn ppm() {
+            let grid = vec_of_vecs::Grid::parse_char_grid("#.\n", |c| c);
+            let image = Image::from_grid(&grid, |_pos, &c| if c == '#' { [255, 0, 0] } else { [0, 0, 0] });
+
+            let mut ppm = Vec::new();
+            image.write_ppm(&mut ppm).unwrap();
+            assert_eq!(ppm, b"P6\n2 1\n255\n\xff\0\0\0\0\0");
+
+            let mut ppm = Vec::new();
+            image.scale(2).write_ppm(&mut ppm).unwrap();
+            assert_eq!(&ppm[..11], b"P6\n4 2\n255\n");
+            assert_eq!(ppm[11..].len(), 4 * 2 * 3);
+            assert_eq!(&ppm[11 + 4 * 3..11 + 5 * 3], [255, 0, 0]);
+        }
+
+        #[test]
+        fn png() {
+            let grid = vec_of_vecs::Grid::parse_char_grid("#.\n.#\n", |c| c);
+            let mut png = Vec::new();
+            Image::from_grid(&grid, |_pos, _c| [0, 0, 0]).write_png(&mut png).unwrap();
+            assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        }
+
+        #[test]
+        fn svg() {
+            let mut svg = Vec::new();
+            Svg::default()
+                .polyline([Pos(0, 0), Pos(0, 6), Pos(-2, 6)], "red")
+                .write(&mut svg)
+                .unwrap();
+            let svg = String::from_utf8(svg).unwrap();
+            assert!(svg.contains(r#"viewBox="-0.5 -2.5 7 3""#));
+            assert!(svg.contains(r#"points="0,0 6,0 6,-2""#));
+        }
     }
 
     pub mod graph {
@@ -561,7 +2141,7 @@ mod lib {
                     G::Edge: Cost,
                 {
                     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-                        self.cost.partial_cmp(&other.cost)
+                        Some(self.cmp(other))
                     }
                 }
 
@@ -636,8 +2216,305 @@ mod lib {
         }
     }
 
+    pub mod interval {
+        //! Half-open intervals of integers, and sets of them.
+
+        /// The integers from `start` up to but not including `end`.
+        ///
+        /// Empty if `end <= start`.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub struct Interval {
+            pub start: isize,
+            pub end: isize,
+        }
+        impl Interval {
+            pub fn new(start: isize, end: isize) -> Self {
+                Self { start, end }
+            }
+
+            /// Returns the `len` integers starting at `start`.
+            pub fn from_len(start: isize, len: isize) -> Self {
+                Self { start, end: start + len }
+            }
+
+            /// Number of integers in the interval.
+            pub fn len(&self) -> usize {
+                (self.end - self.start).max(0) as usize
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.end <= self.start
+            }
+
+            pub fn contains(&self, value: isize) -> bool {
+                self.start <= value && value < self.end
+            }
+
+            /// Returns the integers in both intervals, which may be empty.
+            pub fn intersection(&self, other: &Interval) -> Interval {
+                Interval {
+                    start: self.start.max(other.start),
+                    end: self.end.min(other.end),
+                }
+            }
+
+            /// Splits the interval into the integers below `at`, and those at or above `at`.
+            ///
+            /// Either part may be empty.
+            pub fn split_at(&self, at: isize) -> (Interval, Interval) {
+                let at = at.clamp(self.start, self.end.max(self.start));
+                (Interval { start: self.start, end: at }, Interval { start: at, end: self.end })
+            }
+
+            /// Returns the interval moved by `by`.
+            pub fn shift(&self, by: isize) -> Interval {
+                Interval {
+                    start: self.start + by,
+                    end: self.end + by,
+                }
+            }
+        }
+        impl From<std::ops::Range<isize>> for Interval {
+            fn from(range: std::ops::Range<isize>) -> Self {
+                Self {
+                    start: range.start,
+                    end: range.end,
+                }
+            }
+        }
+
+        /// Set of integers, stored as intervals.
+        ///
+        /// The intervals are kept sorted, non-empty, and apart from each other, so equal sets compare equal.
+        #[derive(Clone, Debug, Default, PartialEq, Eq)]
+        pub struct IntervalSet {
+            intervals: Vec<Interval>,
+        }
+        impl IntervalSet {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub fn min(&self) -> Option<isize> {
+                self.intervals.first().map(|interval| interval.start)
+            }
+
+            pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+                self.intervals.iter().chain(&other.intervals).copied().collect()
+            }
+
+            pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+                let (a, b) = (&self.intervals, &other.intervals);
+                let mut intervals = Vec::new();
+                let (mut i, mut j) = (0, 0);
+                while i < a.len() && j < b.len() {
+                    let intersection = a[i].intersection(&b[j]);
+                    if !intersection.is_empty() {
+                        intervals.push(intersection);
+                    }
+                    // Drop whichever interval ends first, it can't intersect anything further on.
+                    if a[i].end < b[j].end {
+                        i += 1;
+                    } else {
+                        j += 1;
+                    }
+                }
+                intervals.into_iter().collect()
+            }
+
+            /// Returns the integers in `self` that are not in `other`.
+            pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+                let mut intervals = Vec::new();
+                for interval in &self.intervals {
+                    let mut start = interval.start;
+                    for removed in other.intervals.iter().filter(|removed| !removed.intersection(interval).is_empty()) {
+                        intervals.push(Interval::new(start, removed.start));
+                        start = removed.end;
+                    }
+                    intervals.push(Interval::new(start, interval.end));
+                }
+                intervals.into_iter().collect()
+            }
+
+            /// Returns the set moved by `by`.
+            pub fn shift(&self, by: isize) -> IntervalSet {
+                IntervalSet {
+                    intervals: self.intervals.iter().map(|interval| interval.shift(by)).collect(),
+                }
+            }
+
+            /// Restores the invariants: drops empty intervals, sorts, and merges overlapping and adjacent intervals.
+            fn normalize(&mut self) {
+                self.intervals.retain(|interval| !interval.is_empty());
+                self.intervals.sort_unstable_by_key(|interval| interval.start);
+                let mut merged: Vec<Interval> = Vec::with_capacity(self.intervals.len());
+                for interval in self.intervals.drain(..) {
+                    match merged.last_mut() {
+                        Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                        _ => merged.push(interval),
+                    }
+                }
+                self.intervals = merged;
+            }
+        }
+        impl From<Interval> for IntervalSet {
+            fn from(interval: Interval) -> Self {
+                std::iter::once(interval).collect()
+            }
+        }
+        impl FromIterator<Interval> for IntervalSet {
+            fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+                let mut set = IntervalSet {
+                    intervals: iter.into_iter().collect(),
+                };
+                set.normalize();
+                set
+            }
+        }
+
+        #[test]
+        fn interval() {
+            let interval = Interval::from(2..6);
+            assert_eq!(interval, Interval::from_len(2, 4));
+            assert_eq!(interval.len(), 4);
+            assert!(interval.contains(2) && !interval.contains(6));
+            assert_eq!(interval.split_at(4), (Interval::new(2, 4), Interval::new(4, 6)));
+            assert_eq!(interval.split_at(0), (Interval::new(2, 2), Interval::new(2, 6)));
+            assert!(interval.intersection(&Interval::new(6, 8)).is_empty());
+            assert_eq!(Interval::new(3, 1).len(), 0);
+        }
+
+        #[test]
+        fn interval_set() {
+            let set: IntervalSet = [Interval::new(5, 8), Interval::new(0, 2), Interval::new(2, 3), Interval::new(9, 9)]
+                .into_iter()
+                .collect();
+            assert_eq!(set.intervals, [Interval::new(0, 3), Interval::new(5, 8)]);
+            assert_eq!(set.min(), Some(0));
+
+            let other = IntervalSet::from(Interval::new(2, 6));
+            assert_eq!(set.union(&other).intervals, [Interval::new(0, 8)]);
+            assert_eq!(set.intersection(&other).intervals, [Interval::new(2, 3), Interval::new(5, 6)]);
+            assert_eq!(set.difference(&other).intervals, [Interval::new(0, 2), Interval::new(6, 8)]);
+            assert_eq!(set.shift(-10).intervals, [Interval::new(-10, -7), Interval::new(-5, -2)]);
+        }
+
+        #[cfg(test)]
+        mod properties {
+            //! Interval set operations checked against the same operations on sets of the integers themselves.
+            use super::*;
+            use proptest::prelude::*;
+            use std::collections::BTreeSet;
+
+            fn interval_set() -> impl Strategy<Value = IntervalSet> {
+                prop::collection::vec((-50isize..50, 0isize..20), 0..8)
+                    .prop_map(|intervals| intervals.into_iter().map(|(start, len)| Interval::from_len(start, len)).collect())
+            }
+
+            fn integers(set: &IntervalSet) -> BTreeSet<isize> {
+                set.intervals.iter().flat_map(|interval| interval.start..interval.end).collect()
+            }
+
+            fn assert_normalized(set: &IntervalSet) {
+                for interval in &set.intervals {
+                    assert!(!interval.is_empty(), "{set:?} has an empty interval");
+                }
+                for pair in set.intervals.windows(2) {
+                    assert!(pair[0].end < pair[1].start, "{set:?} is not sorted and apart");
+                }
+            }
+
+            proptest! {
+                #[test]
+                fn union(a in interval_set(), b in interval_set()) {
+                    let union = a.union(&b);
+                    assert_normalized(&union);
+                    prop_assert_eq!(integers(&union), &integers(&a) | &integers(&b));
+                }
+
+                #[test]
+                fn intersection(a in interval_set(), b in interval_set()) {
+                    let intersection = a.intersection(&b);
+                    assert_normalized(&intersection);
+                    prop_assert_eq!(integers(&intersection), &integers(&a) & &integers(&b));
+                }
+
+                #[test]
+                fn difference(a in interval_set(), b in interval_set()) {
+                    let difference = a.difference(&b);
+                    assert_normalized(&difference);
+                    prop_assert_eq!(integers(&difference), &integers(&a) - &integers(&b));
+                }
+
+                #[test]
+                fn shift(a in interval_set(), by in -100isize..100) {
+                    let shifted = a.shift(by);
+                    assert_normalized(&shifted);
+                    prop_assert_eq!(integers(&shifted), integers(&a).into_iter().map(|v| v + by).collect::<BTreeSet<_>>());
+                }
+            }
+        }
+    }
+
     /// Utility parsers for nom
     pub mod nom_ext {
+        use crate::SolveError;
+        use nom::combinator::all_consuming;
+        use nom::IResult;
+
+        /// Parses all of `s` with `parser`.
+        pub fn parse_all<'a, O, P>(s: &'a str, parser: P) -> Result<O, SolveError>
+        where
+            P: FnMut(&'a str) -> IResult<&'a str, O>,
+        {
+            crate::lib::parse_time::parsing(|| parse_slice(s, s, parser))
+        }
+
+        /// Parses every line of `s` with `parser`, which must consume the whole line.
+        pub fn parse_lines<'a, O, P>(s: &'a str, mut parser: P) -> Result<Vec<O>, SolveError>
+        where
+            P: FnMut(&'a str) -> IResult<&'a str, O>,
+        {
+            crate::lib::parse_time::parsing(|| s.lines().map(|line| parse_slice(s, line, &mut parser)).collect())
+        }
+
+        /// Parses all of `slice`, which is part of `input`, and reports failures by where they are in `input`.
+        fn parse_slice<'a, O, P>(input: &str, slice: &'a str, parser: P) -> Result<O, SolveError>
+        where
+            P: FnMut(&'a str) -> IResult<&'a str, O>,
+        {
+            match all_consuming(parser)(slice) {
+                Ok((_, output)) => Ok(output),
+                Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+                    let (line, column) = locate(input, err.input);
+                    Err(SolveError::Parse {
+                        line,
+                        column,
+                        kind: err.code,
+                    })
+                }
+                Err(nom::Err::Incomplete(_)) => {
+                    let (line, column) = locate(input, &slice[slice.len()..]);
+                    Err(SolveError::Parse {
+                        line,
+                        column,
+                        kind: nom::error::ErrorKind::Complete,
+                    })
+                }
+            }
+        }
+
+        /// Returns the 1-indexed line and column at which `at` starts within `input`.
+        fn locate(input: &str, at: &str) -> (usize, usize) {
+            let offset = at.as_ptr() as usize - input.as_ptr() as usize;
+            assert!(offset <= input.len(), "`at` is not a slice of `input`");
+            let before = &input[..offset];
+            let line = before.matches('\n').count() + 1;
+            let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+            let column = before[line_start..].chars().count() + 1;
+            (line, column)
+        }
+
         pub mod complete {
             use nom::character::complete::digit1;
             use nom::combinator::map_res;
@@ -652,4 +2529,62 @@ mod lib {
             }
         }
     }
+
+    /// Timing of the shared parsers, for telling parse time apart from solve time.
+    ///
+    /// Nothing is timed until a hook is installed with [`set_hook`].
+    pub mod parse_time {
+        use std::cell::Cell;
+        use std::time::Duration;
+        use std::time::Instant;
+
+        thread_local! {
+            /// Called with the time taken by each outermost [`parsing`] call on this thread.
+            static HOOK: Cell<Option<fn(Duration)>> = const { Cell::new(None) };
+
+            /// Number of [`parsing`] calls currently running on this thread.
+            static DEPTH: Cell<usize> = const { Cell::new(0) };
+        }
+
+        /// Installs the hook that is called with the time taken by each parse on this thread, or removes it if `None`.
+        pub fn set_hook(hook: Option<fn(Duration)>) {
+            HOOK.set(hook);
+        }
+
+        /// Runs `parse`, reporting the time it takes to the hook, if one is installed.
+        ///
+        /// Nested calls are only reported once, by the outermost call.
+        pub fn parsing<T>(parse: impl FnOnce() -> T) -> T {
+            let Some(hook) = HOOK.get() else {
+                return parse();
+            };
+
+            let depth = DEPTH.replace(DEPTH.get() + 1);
+            let now = Instant::now();
+            let result = parse();
+            let elapsed = now.elapsed();
+            DEPTH.set(depth);
+
+            if depth == 0 {
+                hook(elapsed);
+            }
+            result
+        }
+
+        #[test]
+        fn nested() {
+            thread_local! {
+                static CALLS: Cell<usize> = const { Cell::new(0) };
+            }
+            assert_eq!(parsing(|| 1), 1);
+
+            set_hook(Some(|_elapsed| CALLS.set(CALLS.get() + 1)));
+            assert_eq!(parsing(|| parsing(|| 42)), 42);
+            set_hook(None);
+            assert_eq!(parsing(|| 2), 2);
+
+            assert_eq!(CALLS.get(), 1);
+            assert_eq!(DEPTH.get(), 0);
+        }
+    }
 }
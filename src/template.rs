@@ -1,12 +1,16 @@
 //! https://adventofcode.com/2023/day/n
 
-pub fn part_1(input: &str) -> impl std::fmt::Display {
-    let input = parser::parse(input);
-    ""
+use crate::SolveError;
+
+crate::solution!(part_1, part_2);
+
+pub fn part_1(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    let input = parser::parse(input)?;
+    Ok("")
 }
 
-pub fn part_2(input: &str) -> impl std::fmt::Display {
-    ""
+pub fn part_2(input: &str) -> Result<impl std::fmt::Display, SolveError> {
+    Ok("")
 }
 
 struct Line {}
@@ -14,10 +18,9 @@ struct Line {}
 mod parser {
     use super::*;
     use crate::my_nom_prelude::*;
-    use type_toppings::ResultExt as _;
 
-    pub(super) fn parse(s: &str) -> Vec<Line> {
-        s.lines().map(|line| all_consuming(parse_line)(line).expect_or_report(line).1).collect()
+    pub(super) fn parse(s: &str) -> Result<Vec<Line>, SolveError> {
+        parse_lines(s, parse_line)
     }
 
     fn parse_line(s: &str) -> IResult<&str, Line> {
@@ -33,10 +36,10 @@ fn example() -> &'static str {
 
 #[test]
 fn part_1_example() {
-    assert_eq!(part_1(example()).to_string(), "");
+    assert_eq!(part_1(example()).unwrap().to_string(), "");
 }
 
 #[test]
 fn part_2_example() {
-    assert_eq!(part_2(example()).to_string(), "");
+    assert_eq!(part_2(example()).unwrap().to_string(), "");
 }